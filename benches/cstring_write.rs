@@ -0,0 +1,120 @@
+//! Compares repeated appends to owned strings with the length-scanning buffer they replaced
+//! Run with `cargo bench --bench cstring_write`
+use core::fmt::Write;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use cutils::strings::{U16CString, U8CString};
+
+const ITERATIONS: u32 = 10;
+
+// Owned string as it was before the length was stored: the buffer is kept zeroed up to its capacity
+// and the length is found by scanning for nul-terminator
+struct OldCString<T>(Vec<T>);
+
+impl<T: Copy + Default + PartialEq + TryFrom<u32>> OldCString<T> {
+  fn new() -> Self {
+    let mut buf = vec![T::default()];
+    buf.resize(buf.capacity(), T::default());
+    Self(buf)
+  }
+  fn len_usize(&self) -> usize {
+    self.0.iter().take_while(|c| **c != T::default()).count()
+  }
+  fn push_slice(&mut self, data: &[T]) {
+    let prev_len = self.len_usize();
+    self.0.resize(prev_len + data.len() + 1, T::default());
+    self.0[prev_len..prev_len + data.len()].copy_from_slice(data);
+    self.0[prev_len + data.len()] = T::default();
+    let cap = self.0.capacity();
+    self.0.resize(cap, T::default());
+  }
+}
+
+impl<T: Copy + Default + PartialEq + TryFrom<u32>> Write for OldCString<T> {
+  fn write_str(&mut self, s: &str) -> core::fmt::Result {
+    let units = s
+      .chars()
+      .map(|ch| T::try_from(ch as u32).map_err(|_| core::fmt::Error))
+      .collect::<Result<Vec<_>, _>>()?;
+    self.push_slice(&units);
+    Ok(())
+  }
+}
+
+fn measure(name: &str, writes: usize, mut f: impl FnMut() -> usize) -> Duration {
+  let start = Instant::now();
+  for _ in 0..ITERATIONS {
+    black_box(f());
+  }
+  let elapsed = start.elapsed() / ITERATIONS;
+  println!("{:<32} {:>10.3?} per {} writes", name, elapsed, writes);
+  elapsed
+}
+
+fn compare(name: &str, writes: usize, old: impl FnMut() -> usize, new: impl FnMut() -> usize) {
+  let old = measure(&format!("{} (old)", name), writes, old);
+  let new = measure(&format!("{} (new)", name), writes, new);
+  println!("{:<32} {:>9.2}x", format!("{} speedup", name), old.as_secs_f64() / new.as_secs_f64());
+}
+
+fn main() {
+  for writes in [1_000, 10_000] {
+    compare(
+      "U16CString write!",
+      writes,
+      || {
+        let mut string = OldCString::<u16>::new();
+        for i in 0..writes {
+          write!(string, "{},", black_box(i)).unwrap();
+        }
+        string.len_usize()
+      },
+      || {
+        let mut string = U16CString::default();
+        for i in 0..writes {
+          write!(string, "{},", black_box(i)).unwrap();
+        }
+        string.len_usize()
+      },
+    );
+    compare(
+      "U16CString push + len",
+      writes,
+      || {
+        let mut string = OldCString::<u16>::new();
+        for i in 0..writes {
+          string.push_slice(&[b'a' as u16 + (i % 26) as u16]);
+          black_box(string.len_usize());
+        }
+        string.len_usize()
+      },
+      || {
+        let mut string = U16CString::default();
+        for i in 0..writes {
+          string.push(b'a' as u16 + (i % 26) as u16);
+          black_box(string.len_usize());
+        }
+        string.len_usize()
+      },
+    );
+    compare(
+      "U8CString push_slice",
+      writes,
+      || {
+        let mut string = OldCString::<u8>::new();
+        for _ in 0..writes {
+          string.push_slice(black_box(b"abc"));
+        }
+        string.len_usize()
+      },
+      || {
+        let mut string = U8CString::default();
+        for _ in 0..writes {
+          string.push_slice(black_box(b"abc"));
+        }
+        string.len_usize()
+      },
+    );
+  }
+}
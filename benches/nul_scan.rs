@@ -0,0 +1,50 @@
+//! Compares nul-terminator scanning of the crate with the unit-by-unit loop it replaced
+//! Run with `cargo bench --bench nul_scan`
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use cutils::strings::internals::{find_nul_u16, find_nul_u32, find_nul_u8, NulScan};
+
+const LEN: usize = 4 * 1024 * 1024;
+const ITERATIONS: u32 = 50;
+
+// Pointer scan used by `from_ptr`, `from_mut_ptr` and `OwningCStr` before `NulScan`
+fn unit_loop<T: Copy + Default + PartialEq>(data: *const T) -> usize {
+  let mut len = 0;
+  while unsafe { *data.add(len) } != T::default() {
+    len += 1;
+  }
+  len
+}
+
+fn measure(name: &str, mut f: impl FnMut() -> usize) -> Duration {
+  let start = Instant::now();
+  for _ in 0..ITERATIONS {
+    assert_eq!(black_box(f()), LEN);
+  }
+  let elapsed = start.elapsed() / ITERATIONS;
+  println!("{:<24} {:>10.3?} per {} units", name, elapsed, LEN);
+  elapsed
+}
+
+fn compare<T: Copy + Default + PartialEq + NulScan>(unit: &str, data: &[T], find_nul: fn(&[T]) -> Option<usize>) {
+  let old = measure(&format!("{} old unit loop", unit), || unit_loop(black_box(data.as_ptr())));
+  measure(&format!("{} find_nul (slice)", unit), || find_nul(black_box(data)).unwrap());
+  let new = measure(&format!("{} scan_ptr (pointer)", unit), || unsafe {
+    T::scan_ptr(black_box(data.as_ptr()))
+  });
+  println!("{:<24} {:>9.2}x", format!("{} scan_ptr speedup", unit), old.as_secs_f64() / new.as_secs_f64());
+}
+
+fn main() {
+  let mut narrow = vec![b'a'; LEN + 1];
+  narrow[LEN] = 0;
+  let mut wide16 = vec![b'a' as u16; LEN + 1];
+  wide16[LEN] = 0;
+  let mut wide32 = vec![b'a' as u32; LEN + 1];
+  wide32[LEN] = 0;
+
+  compare("u8", &narrow, find_nul_u8);
+  compare("u16", &wide16, find_nul_u16);
+  compare("u32", &wide32, find_nul_u32);
+}
@@ -10,8 +10,12 @@ macro_rules! u16_array {
         let mut i = 0;
         while let Some((ch, rest)) = unsafe { $crate::strings::internals::next_code_point(src) } {
           src = rest;
-          buf[i] = ch as u16;
-          i += 1;
+          let (units, count) = $crate::strings::internals::encode_utf16_units(ch);
+          buf[i] = units[0];
+          if count == 2 {
+            buf[i + 1] = units[1];
+          }
+          i += count;
         }
         buf
       };
@@ -58,8 +62,17 @@ macro_rules! wide_array {
         let mut i = 0;
         while let Some((ch, rest)) = unsafe { $crate::strings::internals::next_code_point(src) } {
           src = rest;
-          buf[i] = ch as $crate::definitions::WChar;
-          i += 1;
+          if core::mem::size_of::<$crate::definitions::WChar>() == 2 {
+            let (units, count) = $crate::strings::internals::encode_utf16_units(ch);
+            buf[i] = units[0] as $crate::definitions::WChar;
+            if count == 2 {
+              buf[i + 1] = units[1] as $crate::definitions::WChar;
+            }
+            i += count;
+          } else {
+            buf[i] = ch as $crate::definitions::WChar;
+            i += 1;
+          }
         }
         buf
       };
@@ -114,6 +127,19 @@ mod tests {
     );
   }
   #[test]
+  fn creates_u16_surrogates() {
+    const U16_ARRAY: [u16; 4] = u16_array!["1\u{1F600}"; 4];
+    assert_eq!(U16_ARRAY, [b'1' as u16, 0xD83D, 0xDE00, 0]);
+  }
+  #[test]
+  fn creates_wide_astral() {
+    const WIDE_ARRAY: [WChar; 4] = wide_array!["\u{1F600}"; 4];
+    #[cfg(windows)]
+    assert_eq!(WIDE_ARRAY, [0xD83D, 0xDE00, 0, 0]);
+    #[cfg(not(windows))]
+    assert_eq!(WIDE_ARRAY, [0x1F600, 0, 0, 0]);
+  }
+  #[test]
   fn creates_u32() {
     const U32_ARRAY: [u32; 10] = u32_array!["123"; 10];
     assert_eq!(
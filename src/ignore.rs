@@ -13,7 +13,7 @@ impl<T> ResultIgnoreExt for Result<T, T> {
   }
 }
 
-#[cfg(not(no_std))]
+#[cfg(feature = "std")]
 impl<T> ResultIgnoreExt for Result<T, std::sync::PoisonError<T>> {
   type Result = T;
   fn ignore(self) -> Self::Result {
@@ -1,25 +1,36 @@
-#![cfg_attr(feature = "no_std", no_std)]
+#![cfg_attr(not(feature = "std"), no_std)]
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+// Names from `std` prelude that are missing from `core` prelude
+#[cfg(feature = "alloc")]
+mod prelude {
+  pub use alloc::{borrow::ToOwned, boxed::Box, format, string::String, vec, vec::Vec};
+}
+
 pub mod arrays;
 pub mod deferred;
 pub mod definitions;
+#[cfg(all(windows, feature = "std"))]
 pub mod files;
 pub mod ignore;
 pub mod inspection;
 pub mod strings;
 pub use cutils_macro::*;
+#[cfg(feature = "std")]
 pub mod errors;
 
 pub fn type_name_of<T>(_: T) -> &'static str {
   core::any::type_name::<T>()
 }
 
-#[cfg(feature = "winapi")]
+#[cfg(all(windows, feature = "winapi"))]
 use winapi::{
   shared::{guiddef::GUID, minwindef::DWORD, ntdef::HANDLE, winerror::ERROR_SUCCESS},
   um::{cfgmgr32::CONFIGRET, errhandlingapi::SetLastError, handleapi::INVALID_HANDLE_VALUE},
 };
 
-#[cfg(feature = "winapi")]
+#[cfg(all(windows, feature = "winapi"))]
 pub fn set_last_error(error: std::io::Error) {
   unsafe {
     SetLastError(
@@ -31,7 +42,7 @@ pub fn set_last_error(error: std::io::Error) {
   }
 }
 
-#[cfg(feature = "winapi")]
+#[cfg(all(windows, feature = "winapi"))]
 pub fn guid_eq(lhs: GUID, rhs: GUID) -> bool {
   lhs.Data1 == rhs.Data1
     && lhs.Data2 == rhs.Data2
@@ -39,7 +50,7 @@ pub fn guid_eq(lhs: GUID, rhs: GUID) -> bool {
     && lhs.Data4 == rhs.Data4
 }
 
-#[cfg(feature = "winapi")]
+#[cfg(all(windows, feature = "winapi"))]
 pub fn code_to_result(code: DWORD) -> std::io::Result<()> {
   if code == ERROR_SUCCESS {
     Ok(())
@@ -48,17 +59,17 @@ pub fn code_to_result(code: DWORD) -> std::io::Result<()> {
   }
 }
 
-#[cfg(feature = "winapi")]
+#[cfg(all(windows, feature = "winapi"))]
 pub fn check_handle(handle: HANDLE) -> bool {
   !handle.is_null() && handle != INVALID_HANDLE_VALUE
 }
 
-#[cfg(feature = "winapi")]
+#[cfg(all(windows, feature = "winapi"))]
 pub trait ErrorFromCrExt {
   fn from_cr(ret: CONFIGRET, default: DWORD) -> Self;
 }
 
-#[cfg(feature = "winapi")]
+#[cfg(all(windows, feature = "winapi"))]
 impl ErrorFromCrExt for std::io::Error {
   fn from_cr(ret: CONFIGRET, default: DWORD) -> Self {
     let err = unsafe { CM_MapCrToWin32Err(ret, default) };
@@ -66,7 +77,7 @@ impl ErrorFromCrExt for std::io::Error {
   }
 }
 
-#[cfg(feature = "winapi")]
+#[cfg(all(windows, feature = "winapi"))]
 extern "system" {
   fn CM_MapCrToWin32Err(CmReturnCode: CONFIGRET, DefaultErr: DWORD) -> DWORD;
 }
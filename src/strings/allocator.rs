@@ -0,0 +1,56 @@
+/// Allocator of memory for C strings that are handed over to (or received from) C code
+///
+/// # Safety
+/// Implementations should return memory suitably aligned for `align`
+/// that stays valid until it is passed to `free` of the same allocator
+pub unsafe trait CAllocator {
+  /// Allocates `size` bytes aligned to at least `align`
+  /// NOTE: returns null pointer in case of allocation failure
+  fn allocate(&self, size: usize, align: usize) -> *mut u8;
+  /// Frees memory previously returned by `allocate`
+  /// # Safety
+  /// `ptr` should be returned by `allocate` of this allocator and should not be used afterwards
+  unsafe fn free(&self, ptr: *mut u8);
+}
+
+/// Allocator using `malloc` and `free` of C runtime,
+/// i.e. strings allocated by it can be freed by C code with `free()`
+/// NOTE: `malloc` alignment is sufficient for all code unit types of the crate
+/// NOTE: implements `CAllocator` only if `libc` feature is enabled
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LibcAlloc;
+
+#[cfg(feature = "libc")]
+extern "C" {
+  fn malloc(size: usize) -> *mut core::ffi::c_void;
+  fn free(ptr: *mut core::ffi::c_void);
+}
+
+#[cfg(feature = "libc")]
+unsafe impl CAllocator for LibcAlloc {
+  fn allocate(&self, size: usize, _align: usize) -> *mut u8 {
+    // malloc(0) is allowed to return null, nul-terminated strings are never empty though
+    unsafe { malloc(size.max(1)) }.cast()
+  }
+  unsafe fn free(&self, ptr: *mut u8) {
+    free(ptr.cast())
+  }
+}
+
+/// Copies `data` into memory allocated by `allocator`
+/// NOTE: returns None in case of allocation failure
+pub(crate) fn alloc_copy_units<T: Copy, A: CAllocator>(allocator: &A, data: &[T]) -> Option<*mut T> {
+  let layout = core::alloc::Layout::for_value(data);
+  let ptr = allocator.allocate(layout.size(), layout.align()).cast::<T>();
+  if ptr.is_null() {
+    return None;
+  }
+  unsafe { core::ptr::copy_nonoverlapping(data.as_ptr(), ptr, data.len()) };
+  Some(ptr)
+}
+
+/// Reports failure to allocate a copy of `data` the same way as collections of `alloc` do
+#[cfg(feature = "alloc")]
+pub(crate) fn alloc_copy_error<T>(data: &[T]) -> ! {
+  alloc::alloc::handle_alloc_error(core::alloc::Layout::for_value(data))
+}
@@ -0,0 +1,85 @@
+use core::cmp::Ordering;
+use core::hash::{Hash, Hasher};
+
+use super::internals::CaseUnit;
+use super::{U16CStr, U32CStr, U8CStr};
+
+/// Wrapper that compares, orders and hashes strings ignoring case
+/// so they can be used as keys of maps and sets
+/// NOTE: characters are compared by their simple (single character) uppercase mapping,
+/// code units that can not be decoded are compared as is
+#[derive(Clone, Copy)]
+pub struct CaseInsensitive<T>(pub T);
+
+macro_rules! case_insensitive_impls {
+  ($name:ident, $type:ty) => {
+    impl<'a> PartialEq for CaseInsensitive<&'a $name> {
+      fn eq(&self, other: &Self) -> bool {
+        <$type>::fold_case(self.0.as_slice()).eq(<$type>::fold_case(other.0.as_slice()))
+      }
+    }
+    impl<'a> Eq for CaseInsensitive<&'a $name> {}
+    impl<'a> PartialOrd for CaseInsensitive<&'a $name> {
+      fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+      }
+    }
+    impl<'a> Ord for CaseInsensitive<&'a $name> {
+      fn cmp(&self, other: &Self) -> Ordering {
+        <$type>::fold_case(self.0.as_slice()).cmp(<$type>::fold_case(other.0.as_slice()))
+      }
+    }
+    impl<'a> Hash for CaseInsensitive<&'a $name> {
+      fn hash<H: Hasher>(&self, state: &mut H) {
+        <$type>::fold_case(self.0.as_slice()).for_each(|ch| state.write_u32(ch));
+        state.write_u8(0xFF);
+      }
+    }
+    impl<'a> core::fmt::Debug for CaseInsensitive<&'a $name> {
+      fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("CaseInsensitive").field(&self.0).finish()
+      }
+    }
+  };
+}
+
+case_insensitive_impls!(U8CStr, u8);
+case_insensitive_impls!(U16CStr, u16);
+case_insensitive_impls!(U32CStr, u32);
+
+#[cfg(test)]
+mod tests {
+  use std::collections::{BTreeSet, HashMap};
+
+  use super::CaseInsensitive;
+  use crate::strings::{U16CString, U8CString};
+
+  #[test]
+  fn keys_map() {
+    let key = U16CString::encode("HKEY_LOCAL_MACHINE\\Software").unwrap();
+    let lookup = U16CString::encode("hkey_local_machine\\SOFTWARE").unwrap();
+    let mut map = HashMap::new();
+    map.insert(CaseInsensitive(key.as_ref()), 1);
+    assert_eq!(map.get(&CaseInsensitive(lookup.as_ref())), Some(&1));
+  }
+  #[test]
+  fn compares_unicode() {
+    let lhs = U16CString::encode("Straße Ä").unwrap();
+    let rhs = U16CString::encode("STRAßE ä").unwrap();
+    assert_eq!(CaseInsensitive(lhs.as_ref()), CaseInsensitive(rhs.as_ref()));
+    let set: BTreeSet<_> = [lhs.as_ref(), rhs.as_ref()].into_iter().map(CaseInsensitive).collect();
+    assert_eq!(set.len(), 1);
+  }
+  #[test]
+  fn orders_utf8() {
+    let lhs = U8CString::encode("abc").unwrap();
+    let rhs = U8CString::encode("ABD").unwrap();
+    assert!(CaseInsensitive(lhs.as_ref()) < CaseInsensitive(rhs.as_ref()));
+    let lhs = U8CString::encode("caf\u{e9}").unwrap();
+    let rhs = U8CString::encode("CAF\u{c9}").unwrap();
+    assert_eq!(CaseInsensitive(lhs.as_ref()), CaseInsensitive(rhs.as_ref()));
+    // Latin-1 byte of 'é' is not valid UTF-8, so it does not match the char
+    let latin1 = U8CString::encode_latin1("caf\u{e9}").unwrap();
+    assert_ne!(CaseInsensitive(lhs.as_ref()), CaseInsensitive(latin1.as_ref()));
+  }
+}
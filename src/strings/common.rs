@@ -1,20 +1,93 @@
+macro_rules! common_debug_impl {
+  ([$($generics:tt)*] $name:ty, $type:ty, $type_name:ident, |$this:ident| $capacity:expr) => {
+    impl<$($generics)*> core::fmt::Debug for $name {
+      /// Prints decoded contents until nul-terminator as a literal,
+      /// alternate form also shows length, capacity and width of the code unit
+      fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let value = $crate::strings::internals::DebugUnits(self.as_slice());
+        if f.alternate() {
+          let $this = self;
+          f.debug_struct(stringify!($type_name))
+            .field("value", &value)
+            .field("len", &self.len_usize())
+            .field("capacity", &$capacity)
+            .field("width", &core::mem::size_of::<$type>())
+            .finish()
+        } else {
+          core::fmt::Debug::fmt(&value, f)
+        }
+      }
+    }
+  };
+}
+
+macro_rules! common_cmp_impls {
+  ([$($generics:tt)*] $name:ty, $type:ty) => {
+    impl<$($generics)*> core::hash::Hash for $name {
+      fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.as_slice().hash(state)
+      }
+    }
+    impl<$($generics)*> core::cmp::Ord for $name {
+      fn cmp(&self, rhs: &Self) -> core::cmp::Ordering {
+        self.as_slice().cmp(rhs.as_slice())
+      }
+    }
+    impl<$($generics)*> core::cmp::PartialEq<[$type]> for $name {
+      fn eq(&self, rhs: &[$type]) -> bool {
+        self.as_slice() == rhs
+      }
+    }
+    impl<$($generics)*> core::cmp::PartialEq<$name> for [$type] {
+      fn eq(&self, rhs: &$name) -> bool {
+        self == rhs.as_slice()
+      }
+    }
+    impl<$($generics)*> core::cmp::PartialEq<str> for $name {
+      fn eq(&self, rhs: &str) -> bool {
+        <$type as $crate::strings::internals::EqStr>::eq_str(self.as_slice(), rhs)
+      }
+    }
+    impl<$($generics)*> core::cmp::PartialEq<&str> for $name {
+      fn eq(&self, rhs: &&str) -> bool {
+        <$type as $crate::strings::internals::EqStr>::eq_str(self.as_slice(), rhs)
+      }
+    }
+    impl<$($generics)*> core::cmp::PartialEq<$name> for str {
+      fn eq(&self, rhs: &$name) -> bool {
+        rhs == self
+      }
+    }
+    impl<$($generics)*> core::cmp::PartialEq<$name> for &str {
+      fn eq(&self, rhs: &$name) -> bool {
+        rhs == self
+      }
+    }
+  };
+}
+
 macro_rules! common_staticcstr_impls {
-  ($name:ident, $type:ty, $into:ty, $asref:ty, $display:ident, $iter:ident, $encode:path) => {
+  ($name:ident, $type:ty, $into:ty, $asref:ty, $display:ident, $iter:ident, $encode:path, $encode_const:path, $find_nul:path) => {
     /// A static str contains it's data on the stack
-    #[derive(Debug, Clone, Copy)]
+    #[derive(Clone, Copy)]
     #[repr(C)]
     pub struct $name<const CAPACITY: usize>([$type; CAPACITY], [$type; 1]);
     impl<const CAPACITY: usize> $crate::strings::CStrCharType for $name<CAPACITY> {
       type Char = $type;
-      fn encode(data: &str) -> Option<Self>
+      fn encode(data: &str) -> Result<Self, $crate::strings::StrError>
       where
         Self: Sized,
       {
-        let vec = $encode(data)?;
-        if vec.len() > CAPACITY {
-          return None;
+        let mut result = Self::zeroed();
+        let needed = $crate::strings::internals::encode_into($encode(data), &mut result.0)?;
+        $crate::strings::internals::check_no_nul(&result.0[..core::cmp::min(needed, CAPACITY)])?;
+        if needed > CAPACITY {
+          return Err($crate::strings::StrError::CapacityExceeded {
+            needed,
+            capacity: CAPACITY,
+          });
         }
-        Some(Self::from_slice(&vec))
+        Ok(result)
       }
     }
     impl<const CAPACITY: usize> $name<CAPACITY> {
@@ -26,9 +99,56 @@ macro_rules! common_staticcstr_impls {
       pub const CAPACITY_DWORD: u32 = CAPACITY as u32;
 
       /// Constructs new empty instance
-      pub fn zeroed() -> Self {
+      pub const fn zeroed() -> Self {
         Self([0 as $type; CAPACITY], [0])
       }
+      /// Encodes `data` into static str, can be evaluated at compile time
+      /// NOTE: panics if `data` contains nul or unrepresentable characters or doesn't fit into `CAPACITY`,
+      /// so using it in const context fails compilation instead
+      pub const fn from_str_const(data: &str) -> Self {
+        match Self::try_from_str_const(data) {
+          Ok(result) => result,
+          Err($crate::strings::StrError::CapacityExceeded { .. }) => panic!("string exceeds capacity of static str"),
+          Err($crate::strings::StrError::InteriorNul { .. }) => panic!("string contains nul character"),
+          Err(_) => panic!("string contains character unrepresentable in target encoding"),
+        }
+      }
+      /// Const implementation of `encode`
+      pub const fn try_from_str_const(data: &str) -> Result<Self, $crate::strings::StrError> {
+        let mut array = [0 as $type; CAPACITY];
+        let mut bytes = data.as_bytes();
+        let mut len = 0;
+        // SAFETY: bytes of &str are valid UTF-8
+        while let Some((ch, rest)) = unsafe { $crate::strings::internals::next_code_point(bytes) } {
+          if ch == 0 {
+            return Err($crate::strings::StrError::InteriorNul { pos: len });
+          }
+          let (units, count) = match $encode_const(ch) {
+            Some(encoded) => encoded,
+            None => {
+              return Err($crate::strings::StrError::InvalidCodePoint {
+                index: data.len() - bytes.len(),
+              })
+            }
+          };
+          let mut i = 0;
+          while i < count {
+            if len + i < CAPACITY {
+              array[len + i] = units[i];
+            }
+            i += 1;
+          }
+          len += count;
+          bytes = rest;
+        }
+        if len > CAPACITY {
+          return Err($crate::strings::StrError::CapacityExceeded {
+            needed: len,
+            capacity: CAPACITY,
+          });
+        }
+        Ok(Self(array, [0]))
+      }
 
       // Waiting for https://github.com/rust-lang/rust/issues/8995 to be stabilized
       // pub type Char = $type;
@@ -38,19 +158,10 @@ macro_rules! common_staticcstr_impls {
       /// searching for nul-terminator character
       /// NOTE: it will never exceed the total `CAPACITY` but can be equal to it
       pub const fn len_usize(&self) -> usize {
-        let mut items = self.0.as_slice();
-        let mut i = 0;
-        while let Some(item) = items.first() {
-          if *item == 0 {
-            return i;
-          }
-          items = match items {
-            [_, rest @ ..] => rest,
-            [] => break,
-          };
-          i += 1
+        match $find_nul(&self.0) {
+          Some(len) => len,
+          None => CAPACITY,
         }
-        i
       }
       /// Checks wheither the static str is empty
       /// i.e. starts with nul-terminator character
@@ -229,6 +340,12 @@ macro_rules! common_staticcstr_impls {
           if *item == 0 {
             break;
           }
+          if i == CAPACITY {
+            return Err($crate::strings::StrError::CapacityExceeded {
+              needed: CAPACITY + 1,
+              capacity: CAPACITY,
+            });
+          }
           (array[i], inf_buf) = match inf_buf {
             [first, rest @ ..] => (*first, rest),
             [] => return Err($crate::strings::StrError::NulNotFound),
@@ -269,6 +386,7 @@ macro_rules! common_staticcstr_impls {
       }
       /// Copies data from data pointed to by `data` to the static string and returns it
       /// NOTE: this function returns Err in case of nul-terminator was not found in first `max_len` characters
+      /// or in case of string does not fit into `CAPACITY`
       pub const unsafe fn from_ptr_n(
         data: *const $type,
         max_len: usize,
@@ -280,6 +398,12 @@ macro_rules! common_staticcstr_impls {
           if *item == 0 {
             break;
           }
+          if i == CAPACITY {
+            return Err($crate::strings::StrError::CapacityExceeded {
+              needed: CAPACITY + 1,
+              capacity: CAPACITY,
+            });
+          }
           (array[i], buf) = match buf {
             [first, rest @ ..] => (*first, rest),
             [] => return Err($crate::strings::StrError::NulNotFound),
@@ -298,8 +422,9 @@ macro_rules! common_staticcstr_impls {
         $display(&self.0[0..self.len_usize()])
       }
       /// Tries to construct static str from slice
-      /// NOTE: in case of slice does not contain nul-terminator in
-      /// first `CAPACITY` characters this function returns Err
+      /// NOTE: in case of slice does not contain nul-terminator this function returns
+      /// `StrError::NulNotFound` and in case of string before nul-terminator is longer
+      /// then `CAPACITY` it returns `StrError::CapacityExceeded`
       pub const fn try_from_slice(value: &[$type]) -> Result<Self, $crate::strings::StrError> {
         // let len = value.iter().take_while(|c| **c != 0).count();
         let mut buf = value;
@@ -314,10 +439,16 @@ macro_rules! common_staticcstr_impls {
           };
           len += 1;
         }
-        if len == value.len() || len >= CAPACITY {
+        if len == value.len() {
           Err($crate::strings::StrError::NulNotFound)
+        } else if len > CAPACITY {
+          Err($crate::strings::StrError::CapacityExceeded {
+            needed: len,
+            capacity: CAPACITY,
+          })
         } else {
-          Ok(Self::from_slice(value))
+          // Const implementation of: "Self::from_slice(&value[..len])"
+          Ok(Self::from_slice(unsafe { core::slice::from_raw_parts(value.as_ptr(), len) }))
         }
       }
       /// Returns an iterator over characters of the static str
@@ -338,6 +469,98 @@ macro_rules! common_staticcstr_impls {
       pub fn into_iter(self) -> $iter<$name<CAPACITY>> {
         $iter(self, 0)
       }
+      /// Places nul-terminator after first `len` characters
+      fn terminate_at(&mut self, len: usize) {
+        if len < CAPACITY {
+          self.0[len] = 0;
+        }
+      }
+      /// Appends a character to the end of the static str
+      /// NOTE: this method returns Err in case of resulting string does not fit into `CAPACITY`
+      pub fn try_push(&mut self, ch: $type) -> Result<(), $crate::strings::StrError> {
+        self.try_push_slice(&[ch])
+      }
+      /// Appends characters to the end of the static str
+      /// NOTE: this method returns Err in case of resulting string does not fit into `CAPACITY`
+      pub fn try_push_slice(&mut self, data: &[$type]) -> Result<(), $crate::strings::StrError> {
+        let len = self.len_usize();
+        self.try_insert_slice(len, data)
+      }
+      /// Appends contents of `data` to the end of the static str
+      /// NOTE: this method returns Err in case of resulting string does not fit into `CAPACITY`
+      pub fn try_push_str(&mut self, data: &$asref) -> Result<(), $crate::strings::StrError> {
+        self.try_push_slice(data.as_slice())
+      }
+      /// Encodes `ch` and appends it to the end of the static str
+      /// NOTE: this method returns Err in case of `ch` is unrepresentable
+      /// or resulting string does not fit into `CAPACITY`
+      pub fn try_push_char(&mut self, ch: char) -> Result<(), $crate::strings::StrError> {
+        let mut encoded = [0 as $type; 4];
+        let len = $crate::strings::internals::encode_into($encode(ch.encode_utf8(&mut [0; 4])), &mut encoded)?;
+        self.try_push_slice(&encoded[..len])
+      }
+      /// Inserts a character at position `index`
+      /// NOTE: this method returns Err in case of resulting string does not fit into `CAPACITY`
+      /// NOTE: panics if `index` is greater then the length of the static str
+      pub fn try_insert(&mut self, index: usize, ch: $type) -> Result<(), $crate::strings::StrError> {
+        self.try_insert_slice(index, &[ch])
+      }
+      /// Inserts contents of `data` at position `index`
+      /// NOTE: this method returns Err in case of resulting string does not fit into `CAPACITY`
+      /// NOTE: panics if `index` is greater then the length of the static str
+      pub fn try_insert_str(&mut self, index: usize, data: &$asref) -> Result<(), $crate::strings::StrError> {
+        self.try_insert_slice(index, data.as_slice())
+      }
+      /// Inserts characters at position `index`
+      /// NOTE: this method returns Err in case of resulting string does not fit into `CAPACITY`
+      /// NOTE: panics if `index` is greater then the length of the static str
+      pub fn try_insert_slice(&mut self, index: usize, data: &[$type]) -> Result<(), $crate::strings::StrError> {
+        let len = self.len_usize();
+        assert!(index <= len, "insertion index (is {}) should be <= len (is {})", index, len);
+        let new_len = len + data.len();
+        if new_len > CAPACITY {
+          return Err($crate::strings::StrError::CapacityExceeded {
+            needed: new_len,
+            capacity: CAPACITY,
+          });
+        }
+        self.0.copy_within(index..len, index + data.len());
+        self.0[index..index + data.len()].copy_from_slice(data);
+        self.terminate_at(new_len);
+        Ok(())
+      }
+      /// Removes the character at position `index` and returns it
+      /// NOTE: panics if `index` is out of bounds of the static str
+      pub fn remove(&mut self, index: usize) -> $type {
+        let len = self.len_usize();
+        assert!(index < len, "removal index (is {}) should be < len (is {})", index, len);
+        let ch = self.0[index];
+        self.0.copy_within(index + 1..len, index);
+        self.0[len - 1] = 0;
+        ch
+      }
+      /// Removes the last character and returns it
+      pub fn pop(&mut self) -> Option<$type> {
+        let len = self.len_usize().checked_sub(1)?;
+        Some(self.remove(len))
+      }
+      /// Retains only the characters specified by the predicate
+      pub fn retain(&mut self, mut f: impl FnMut($type) -> bool) {
+        let len = self.len_usize();
+        let mut kept = 0;
+        for i in 0..len {
+          let ch = self.0[i];
+          if f(ch) {
+            self.0[kept] = ch;
+            kept += 1;
+          }
+        }
+        self.terminate_at(kept);
+      }
+      /// Makes the static str empty
+      pub fn clear(&mut self) {
+        self.terminate_at(0);
+      }
     }
     impl<const CAPACITY: usize> Default for $name<CAPACITY> {
       fn default() -> Self {
@@ -356,6 +579,7 @@ macro_rules! common_staticcstr_impls {
         Self::try_from_slice(value)
       }
     }
+    #[cfg(feature = "alloc")]
     impl<const CAPACITY: usize> From<$name<CAPACITY>> for $into {
       fn from(value: $name<CAPACITY>) -> Self {
         Self::from(&value.0)
@@ -492,13 +716,78 @@ macro_rules! common_staticcstr_impls {
       }
     }
     impl<const CAP: usize> core::cmp::Eq for $name<CAP> {}
+    impl<const CAP1: usize, const CAP2: usize> core::cmp::PartialOrd<$name<CAP1>> for $name<CAP2> {
+      fn partial_cmp(&self, rhs: &$name<CAP1>) -> Option<core::cmp::Ordering> {
+        Some(self.as_slice().cmp(rhs.as_slice()))
+      }
+    }
+    impl<const CAPACITY: usize> core::fmt::Write for $name<CAPACITY> {
+      /// Encodes `s` and appends it to the end of the static str
+      /// NOTE: u8 strs are encoded as UTF-8, u16 as UTF-16 and u32 as UTF-32
+      /// NOTE: this method returns Err in case of `s` contains nul or does not fit into `CAPACITY`
+      fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        use $crate::strings::internals::FmtUnit;
+        let len = self.len_usize();
+        let encoded_len = <$type as FmtUnit>::encoded_len(s);
+        if s.contains('\0') || encoded_len > CAPACITY - len {
+          return Err(core::fmt::Error);
+        }
+        for (slot, unit) in self.0[len..].iter_mut().zip(<$type as FmtUnit>::encode_str(s)) {
+          *slot = unit;
+        }
+        if let Some(slot) = self.0.get_mut(len + encoded_len) {
+          *slot = 0;
+        }
+        Ok(())
+      }
+    }
+    #[cfg(feature = "std")]
+    impl<const CAPACITY: usize> $crate::strings::CStrSink for $name<CAPACITY> {
+      type Char = $type;
+      fn push_units(&mut self, units: &[$type]) -> bool {
+        self.try_push_slice(units).is_ok()
+      }
+    }
+    common_cmp_impls!([const CAP: usize] $name<CAP>, $type);
+    common_debug_impl!([const CAP: usize] $name<CAP>, $type, $name, |_this| CAP);
+    impl<const CAP: usize> core::cmp::PartialEq<$asref> for $name<CAP> {
+      fn eq(&self, rhs: &$asref) -> bool {
+        self.as_slice() == rhs.as_slice()
+      }
+    }
+    impl<const CAP: usize> core::cmp::PartialEq<&$asref> for $name<CAP> {
+      fn eq(&self, rhs: &&$asref) -> bool {
+        self.as_slice() == rhs.as_slice()
+      }
+    }
+    impl<const CAP: usize> core::cmp::PartialEq<$name<CAP>> for $asref {
+      fn eq(&self, rhs: &$name<CAP>) -> bool {
+        self.as_slice() == rhs.as_slice()
+      }
+    }
+    impl<const CAP: usize> core::cmp::PartialEq<$name<CAP>> for &$asref {
+      fn eq(&self, rhs: &$name<CAP>) -> bool {
+        self.as_slice() == rhs.as_slice()
+      }
+    }
+    #[cfg(feature = "alloc")]
+    impl<const CAP: usize> core::cmp::PartialEq<$into> for $name<CAP> {
+      fn eq(&self, rhs: &$into) -> bool {
+        self.as_slice() == rhs.as_slice()
+      }
+    }
+    #[cfg(feature = "alloc")]
+    impl<const CAP: usize> core::cmp::PartialEq<$name<CAP>> for $into {
+      fn eq(&self, rhs: &$name<CAP>) -> bool {
+        self.as_slice() == rhs.as_slice()
+      }
+    }
   };
 }
 
 macro_rules! common_cstr_impls {
-  ($name:ident, $type:ty, $into:ty, $display:ident, $iter:ident, $static:ident) => {
+  ($name:ident, $type:ty, $into:ty, $display:ident, $iter:ident, $static:ident, $find_nul:path, $find_nul_ptr:path) => {
     /// A wrapper struct for slice of characters
-    #[derive(Debug)]
     #[repr(transparent)]
     pub struct $name([$type]);
     impl $crate::strings::CStrCharType for $name {
@@ -527,19 +816,10 @@ macro_rules! common_cstr_impls {
       /// Calculates the length of a cstr slice by
       /// iterating over characters in search of nul-terminator
       pub const fn len_usize(&self) -> usize {
-        let mut items = &self.0;
-        let mut i = 0;
-        while let Some(item) = items.first() {
-          if *item == 0 {
-            return i;
-          }
-          items = match items {
-            [_, rest @ ..] => rest,
-            [] => break,
-          };
-          i += 1
+        match $find_nul(&self.0) {
+          Some(len) => len,
+          None => panic!("CStr was in invalid state. Missing nul-terminator"),
         }
-        panic!("CStr was in invalid state. Missing nul-terminator")
       }
       /// Calculates the length of a cstr slice by
       /// iterating over characters in search of nul-terminator
@@ -709,30 +989,18 @@ macro_rules! common_cstr_impls {
       }
       #[doc = concat!("
       Constructs an instance of immutable cstr given a pointer to a constant string
-      SAFETY: `data` should point to a valid memory where
       NOTE: this function can be dangerous because of not constaining length, 
       consider using safer funtion: `", stringify!($name), "::from_ptr_n`
-      cstring is stored and this cstring should end with a nul-terminator
       NOTE: lifetime of the returned value is inferred from context
+      NOTE: usable in const context, so the terminator is searched unit by unit
+      # Safety
+      `data` should point to a valid memory where
+      cstring is stored and this cstring should end with a nul-terminator
       ")]
       pub const unsafe fn from_ptr<'a>(data: *const $type) -> &'a Self {
-        let mut inf_buf = core::slice::from_raw_parts(data, usize::MAX);
-        // Const implementation of: "let len = inf_buf.iter().take_while(|c| **c != 0).count();"
-        // -----
-        let mut len = 0;
-        while let Some(item) = inf_buf.first() {
-          if *item == 0 {
-            break;
-          }
-          inf_buf = match inf_buf {
-            [_, rest @ ..] => rest,
-            [] => unreachable!(),
-          };
-          len += 1;
-        }
-        // -----
+        let len = $find_nul_ptr(data);
         let buf = core::slice::from_raw_parts(data, len + 1);
-        core::mem::transmute(buf)
+        core::mem::transmute::<&[$type], &Self>(buf)
       }
       #[doc = concat!("
       Constructs an instance of mutable cstr given a pointer to a mutable string
@@ -743,8 +1011,7 @@ macro_rules! common_cstr_impls {
       NOTE: lifetime of the returned value is inferred from context
       ")]
       pub unsafe fn from_mut_ptr<'a>(data: *mut $type) -> &'a mut Self {
-        let inf_buf = core::slice::from_raw_parts(data, usize::MAX);
-        let len = inf_buf.iter().take_while(|c| **c != 0).count();
+        let len = <$type as $crate::strings::internals::NulScan>::scan_ptr(data);
         let buf = core::slice::from_raw_parts_mut(data, len + 1);
         core::mem::transmute(buf)
       }
@@ -775,27 +1042,11 @@ macro_rules! common_cstr_impls {
         data: *const $type,
         max_len: usize,
       ) -> Result<&'a Self, $crate::strings::StrError> {
-        let mut inf_buf = core::slice::from_raw_parts(data, max_len);
-        // Const implementation of: "let len = inf_buf.iter().take_while(|c| **c != 0).count();"
-        // -----
-        let mut len = 0;
-        while let Some(item) = inf_buf.first() {
-          if *item == 0 {
-            break;
-          }
-          len += 1;
-          inf_buf = match inf_buf {
-            [_, rest @ ..] => rest,
-            [] => break,
-          };
-        }
-        // -----
-        if len == max_len {
-          Err($crate::strings::StrError::NulNotFound)
-        } else {
-          let buf = core::slice::from_raw_parts(data, len + 1);
-          Ok(core::mem::transmute(buf))
-        }
+        let Some(len) = $find_nul(core::slice::from_raw_parts(data, max_len)) else {
+          return Err($crate::strings::StrError::NulNotFound);
+        };
+        let buf = core::slice::from_raw_parts(data, len + 1);
+        Ok(core::mem::transmute::<&[$type], &Self>(buf))
       }
       /// Constructs an instance of mutable cstr given a pointer to a mutable string and a maximum length.
       /// If a string does not contain nul-terminator in first `max_len` characters,
@@ -806,14 +1057,11 @@ macro_rules! common_cstr_impls {
         data: *mut $type,
         max_len: usize,
       ) -> Result<&'a mut Self, $crate::strings::StrError> {
-        let inf_buf = core::slice::from_raw_parts(data, max_len);
-        let len = inf_buf.iter().take_while(|c| **c != 0).count();
-        if len == max_len {
-          Err($crate::strings::StrError::NulNotFound)
-        } else {
-          let buf = core::slice::from_raw_parts_mut(data, len + 1);
-          Ok(core::mem::transmute(buf))
-        }
+        let Some(len) = $find_nul(core::slice::from_raw_parts(data, max_len)) else {
+          return Err($crate::strings::StrError::NulNotFound);
+        };
+        let buf = core::slice::from_raw_parts_mut(data, len + 1);
+        Ok(core::mem::transmute::<&mut [$type], &mut Self>(buf))
       }
       /// Provides a wrapper that implements core::fmt::Display
       pub fn display<'a>(&'a self) -> $display<'a> {
@@ -837,11 +1085,17 @@ macro_rules! common_cstr_impls {
         };
         Ok(unsafe { core::mem::transmute(value) })
       }
+      /// Copies contents until nul-terminator to the static str
+      /// NOTE: this function returns `StrError::CapacityExceeded` in case of contents does not fit into `CAPACITY`
       pub fn try_into_static<const CAPACITY: usize>(&self) -> Result<$static<CAPACITY>, $crate::strings::StrError> {
-        if self.len_usize() > CAPACITY {
-          return Err($crate::strings::StrError::NulNotFound);
+        let len = self.len_usize();
+        if len > CAPACITY {
+          return Err($crate::strings::StrError::CapacityExceeded {
+            needed: len,
+            capacity: CAPACITY,
+          });
         }
-        Ok(<$static<CAPACITY>>::from_slice(&self.0))
+        Ok(<$static<CAPACITY>>::from_slice(self.as_slice()))
       }
       pub fn iter_strs<'a>(&'a self) -> impl Iterator<Item=&'a $name> {
         struct Iter<'a> {
@@ -863,6 +1117,106 @@ macro_rules! common_cstr_impls {
         }
         Iter{inner: self}
       }
+      /// Checks whether the cstr starts with `prefix`
+      pub fn starts_with(&self, prefix: &[$type]) -> bool {
+        self.as_slice().starts_with(prefix)
+      }
+      /// Checks whether the cstr ends with `suffix`
+      pub fn ends_with(&self, suffix: &[$type]) -> bool {
+        self.as_slice().ends_with(suffix)
+      }
+      /// Returns the index of the first occurrence of `needle` in the cstr
+      pub fn find(&self, needle: &[$type]) -> Option<usize> {
+        if needle.is_empty() {
+          return Some(0);
+        }
+        self.as_slice().windows(needle.len()).position(|window| window == needle)
+      }
+      /// Returns an iterator over parts of the cstr separated by `separator`
+      pub fn split(&self, separator: $type) -> impl Iterator<Item = &[$type]> {
+        self.as_slice().split(move |ch| *ch == separator)
+      }
+      /// Returns the cstr without leading whitespace
+      pub fn trim_start(&self) -> &Self {
+        let skip = self
+          .as_slice()
+          .iter()
+          .take_while(|ch| $crate::strings::internals::is_whitespace_unit(**ch as u32))
+          .count();
+        self.range(skip..)
+      }
+      /// Returns contents of the cstr without trailing whitespace
+      pub fn trim_end(&self) -> &[$type] {
+        let slice = self.as_slice();
+        let skip = slice
+          .iter()
+          .rev()
+          .take_while(|ch| $crate::strings::internals::is_whitespace_unit(**ch as u32))
+          .count();
+        &slice[..slice.len() - skip]
+      }
+      /// Returns contents of the cstr without leading and trailing whitespace
+      pub fn trim(&self) -> &[$type] {
+        self.trim_start().trim_end()
+      }
+      #[cfg(feature = "alloc")]
+      /// Returns a copy of the cstr with all occurrences of `from` replaced with `to`
+      /// NOTE: in case of `from` is empty the contents are copied unchanged
+      pub fn replace(&self, from: &[$type], to: &[$type]) -> $into {
+        let mut result = Vec::with_capacity(self.len_with_nul_usize());
+        let mut rest = self.as_slice();
+        if !from.is_empty() {
+          while let Some(pos) = rest.windows(from.len()).position(|window| window == from) {
+            result.extend_from_slice(&rest[..pos]);
+            result.extend_from_slice(to);
+            rest = &rest[pos + from.len()..];
+          }
+        }
+        result.extend_from_slice(rest);
+        <$into>::from(result)
+      }
+      /// Checks that two cstrs are equal ignoring case of ASCII characters
+      pub fn eq_ignore_ascii_case(&self, other: &Self) -> bool {
+        use $crate::strings::internals::CaseUnit;
+        let (lhs, rhs) = (self.as_slice(), other.as_slice());
+        lhs.len() == rhs.len() && lhs.iter().zip(rhs).all(|(l, r)| l.ascii_lower() == r.ascii_lower())
+      }
+      /// Converts ASCII characters of the cstr to lowercase in-place
+      pub fn make_ascii_lowercase(&mut self) {
+        use $crate::strings::internals::CaseUnit;
+        self.as_mut_slice().iter_mut().for_each(|ch| *ch = ch.ascii_lower());
+      }
+      /// Converts ASCII characters of the cstr to uppercase in-place
+      pub fn make_ascii_uppercase(&mut self) {
+        use $crate::strings::internals::CaseUnit;
+        self.as_mut_slice().iter_mut().for_each(|ch| *ch = ch.ascii_upper());
+      }
+      #[cfg(feature = "alloc")]
+      /// Returns a copy of the cstr with ASCII characters converted to lowercase
+      pub fn to_ascii_lowercase(&self) -> $into {
+        use $crate::strings::internals::CaseUnit;
+        <$into>::from(self.as_slice().iter().map(|ch| ch.ascii_lower()).collect::<Vec<_>>())
+      }
+      #[cfg(feature = "alloc")]
+      /// Returns a copy of the cstr with ASCII characters converted to uppercase
+      pub fn to_ascii_uppercase(&self) -> $into {
+        use $crate::strings::internals::CaseUnit;
+        <$into>::from(self.as_slice().iter().map(|ch| ch.ascii_upper()).collect::<Vec<_>>())
+      }
+      #[cfg(feature = "alloc")]
+      /// Returns the lowercase equivalent of the cstr
+      /// NOTE: characters that can not be decoded or whose lowercase form
+      /// is not representable in the encoding are kept unchanged
+      pub fn to_lowercase(&self) -> $into {
+        <$into>::from(<$type as $crate::strings::internals::CaseUnit>::convert_case(self.as_slice(), false))
+      }
+      #[cfg(feature = "alloc")]
+      /// Returns the uppercase equivalent of the cstr
+      /// NOTE: characters that can not be decoded or whose uppercase form
+      /// is not representable in the encoding are kept unchanged
+      pub fn to_uppercase(&self) -> $into {
+        <$into>::from(<$type as $crate::strings::internals::CaseUnit>::convert_case(self.as_slice(), true))
+      }
     }
     impl<'a> TryFrom<&'a [$type]> for &'a $name {
       type Error = $crate::strings::StrError;
@@ -888,17 +1242,80 @@ macro_rules! common_cstr_impls {
         <$name>::try_from_mut_slice(value)
       }
     }
+    #[cfg(feature = "alloc")]
     impl From<&$name> for $into {
       fn from(value: &$name) -> Self {
         Self::from(&value.0)
       }
     }
+    #[cfg(feature = "alloc")]
     impl ToOwned for $name {
       type Owned = $into;
       fn to_owned(&self) -> Self::Owned {
         self.into()
       }
     }
+    #[cfg(feature = "alloc")]
+    impl<'a> From<&'a $name> for alloc::borrow::Cow<'a, $name> {
+      fn from(value: &'a $name) -> Self {
+        alloc::borrow::Cow::Borrowed(value)
+      }
+    }
+    #[cfg(feature = "alloc")]
+    impl<'a> From<$into> for alloc::borrow::Cow<'a, $name> {
+      fn from(value: $into) -> Self {
+        alloc::borrow::Cow::Owned(value)
+      }
+    }
+    #[cfg(feature = "alloc")]
+    impl From<&$name> for Box<$name> {
+      /// NOTE: only contents until nul-terminator (and including it) are copied
+      fn from(value: &$name) -> Self {
+        let boxed: Box<[$type]> = value.as_slice_with_nul().into();
+        unsafe { Box::from_raw(Box::into_raw(boxed) as *mut $name) }
+      }
+    }
+    #[cfg(feature = "alloc")]
+    impl From<&$name> for alloc::rc::Rc<$name> {
+      /// NOTE: only contents until nul-terminator (and including it) are copied
+      fn from(value: &$name) -> Self {
+        let rc: alloc::rc::Rc<[$type]> = value.as_slice_with_nul().into();
+        unsafe { alloc::rc::Rc::from_raw(alloc::rc::Rc::into_raw(rc) as *const $name) }
+      }
+    }
+    #[cfg(feature = "alloc")]
+    impl From<&$name> for alloc::sync::Arc<$name> {
+      /// NOTE: only contents until nul-terminator (and including it) are copied
+      fn from(value: &$name) -> Self {
+        let arc: alloc::sync::Arc<[$type]> = value.as_slice_with_nul().into();
+        unsafe { alloc::sync::Arc::from_raw(alloc::sync::Arc::into_raw(arc) as *const $name) }
+      }
+    }
+    #[cfg(feature = "alloc")]
+    impl From<$into> for Box<$name> {
+      /// NOTE: buffer is truncated after nul-terminator and shrunk to fit
+      fn from(value: $into) -> Self {
+        let len = value.len_with_nul_usize();
+        let mut buf = value.into_inner();
+        buf.truncate(len);
+        let boxed = buf.into_boxed_slice();
+        unsafe { Box::from_raw(Box::into_raw(boxed) as *mut $name) }
+      }
+    }
+    #[cfg(feature = "alloc")]
+    impl From<Box<$name>> for $into {
+      /// NOTE: reuses the allocation of the box
+      fn from(value: Box<$name>) -> Self {
+        let boxed = unsafe { Box::from_raw(Box::into_raw(value) as *mut [$type]) };
+        Self::from(boxed.into_vec())
+      }
+    }
+    #[cfg(feature = "alloc")]
+    impl Clone for Box<$name> {
+      fn clone(&self) -> Self {
+        Self::from(&**self)
+      }
+    }
     impl AsRef<$name> for &$name {
       fn as_ref(&self) -> &$name {
         self
@@ -982,44 +1399,120 @@ macro_rules! common_cstr_impls {
       }
     }
     impl core::cmp::Eq for $name {}
+    impl core::cmp::PartialOrd for $name {
+      fn partial_cmp(&self, rhs: &$name) -> Option<core::cmp::Ordering> {
+        Some(core::cmp::Ord::cmp(self, rhs))
+      }
+    }
+    impl core::fmt::Write for &mut $name {
+      /// Encodes `s` into the beginning of the cstr and advances it past the written characters,
+      /// so that the next write continues after them
+      /// NOTE: u8 cstrs are encoded as UTF-8, u16 as UTF-16 and u32 as UTF-32
+      /// NOTE: this method returns Err in case of `s` contains nul or does not fit into the cstr
+      fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        use $crate::strings::internals::FmtUnit;
+        let encoded_len = <$type as FmtUnit>::encoded_len(s);
+        if s.contains('\0') || encoded_len > self.capacity_usize() {
+          return Err(core::fmt::Error);
+        }
+        for (slot, unit) in self.0.iter_mut().zip(<$type as FmtUnit>::encode_str(s)) {
+          *slot = unit;
+        }
+        self.0[encoded_len] = 0;
+        *self = unsafe { core::mem::transmute::<&mut [$type], &mut $name>(&mut self.0[encoded_len..]) };
+        Ok(())
+      }
+    }
+    #[cfg(feature = "std")]
+    impl $crate::strings::CStrSink for &mut $name {
+      type Char = $type;
+      /// Writes `units` at the beginning of the cstr and advances it past them
+      fn push_units(&mut self, units: &[$type]) -> bool {
+        if units.len() > self.capacity_usize() {
+          return false;
+        }
+        self.0[..units.len()].copy_from_slice(units);
+        self.0[units.len()] = 0;
+        *self = unsafe { core::mem::transmute::<&mut [$type], &mut $name>(&mut self.0[units.len()..]) };
+        true
+      }
+    }
+    common_cmp_impls!([] $name, $type);
+    common_debug_impl!([] $name, $type, $name, |this| this.capacity_usize());
+    #[cfg(feature = "alloc")]
+    impl core::cmp::PartialEq<$into> for $name {
+      fn eq(&self, rhs: &$into) -> bool {
+        self.as_slice() == rhs.as_slice()
+      }
+    }
+    #[cfg(feature = "alloc")]
+    impl core::cmp::PartialEq<$into> for &$name {
+      fn eq(&self, rhs: &$into) -> bool {
+        self.as_slice() == rhs.as_slice()
+      }
+    }
+    #[cfg(feature = "alloc")]
+    impl core::cmp::PartialEq<$name> for $into {
+      fn eq(&self, rhs: &$name) -> bool {
+        self.as_slice() == rhs.as_slice()
+      }
+    }
+    #[cfg(feature = "alloc")]
+    impl core::cmp::PartialEq<&$name> for $into {
+      fn eq(&self, rhs: &&$name) -> bool {
+        self.as_slice() == rhs.as_slice()
+      }
+    }
   };
 }
 
+#[cfg(feature = "alloc")]
 macro_rules! common_cstring_impls {
-  ($name:ident, $type:ty, $asref:ty, $display:ident, $iter:ident, $encode:path) => {
-    #[derive(Debug, Clone)]
+  ($name:ident, $type:ty, $asref:ty, $display:ident, $iter:ident, $guard:ident, $encode:path, $find_nul:path) => {
+    /// An owned nul-terminated string
+    /// NOTE: the vector holds the contents followed by a single nul-terminator,
+    /// so the length is known without scanning and the spare capacity is never initialized
+    /// NOTE: the contents are borrowed mutably through `as_mut_str`,
+    /// which recalculates the length in case of nul characters were written
+    #[derive(Clone)]
     pub struct $name(Vec<$type>);
     impl $crate::strings::CStrCharType for $name {
       type Char = $type;
-      fn encode(data: &str) -> Option<Self>
+      fn encode(data: &str) -> Result<Self, $crate::strings::StrError>
       where
         Self: Sized,
       {
         let vec = $encode(data)?;
-        Some(Self::from(vec))
+        Ok(Self::new(vec)?)
       }
     }
     impl $name {
-      pub fn new() -> Self {
-        let mut buf = vec![0 as $type];
-        buf.resize(buf.capacity(), 0);
-        Self(buf)
+      /// Constructs a string from `data` appending nul-terminator to it
+      /// NOTE: in case of `data` contains nul character Err is returned,
+      /// it holds the position of nul character and gives back the vector
+      pub fn new(data: impl Into<Vec<$type>>) -> Result<Self, $crate::strings::InteriorNulError<$type>> {
+        let mut buf = data.into();
+        if let Some(pos) = buf.iter().position(|c| *c == 0) {
+          return Err($crate::strings::InteriorNulError::new(pos, buf));
+        }
+        buf.push(0);
+        Ok(Self(buf))
       }
+      /// Constructs an empty string that can hold at least `cap` characters without reallocation
       pub fn with_capacity(cap: usize) -> Self {
-        let mut buf = vec![0 as $type; cap + 1];
-        buf.resize(buf.capacity(), 0);
+        let mut buf = Vec::with_capacity(cap + 1);
+        buf.push(0);
         Self(buf)
       }
+      /// Reserves capacity for at least `total` characters (excluding nul-terminator)
       pub fn reserve<T: TryInto<usize>>(&mut self, total: T) {
-        let cap = self.0.capacity();
-        self
-          .0
-          .resize(std::cmp::max(total.try_into().unwrap_or(0), cap), 0);
-        let cap = self.0.capacity();
-        self.0.resize(cap, 0);
+        let total: usize = total.try_into().unwrap_or(0);
+        self.0.reserve((total + 1).saturating_sub(self.0.len()));
       }
+      /// Returns the length of the string
+      /// NOTE: the length is stored, so this method does not scan for nul-terminator
       pub fn len_usize(&self) -> usize {
-        self.0.iter().take_while(|c| **c != 0).count()
+        self.0.len() - 1
       }
       pub fn len_dword(&self) -> u32 {
         self.len_usize() as u32
@@ -1050,8 +1543,10 @@ macro_rules! common_cstring_impls {
           .try_into()
           .unwrap_or_default()
       }
+      /// Returns the number of characters the string can hold without reallocation
+      /// (excluding nul-terminator)
       pub fn capacity_usize(&self) -> usize {
-        self.0.len() - 1
+        self.0.capacity() - 1
       }
       pub fn capacity_dword(&self) -> u32 {
         self.capacity_usize() as u32
@@ -1059,10 +1554,51 @@ macro_rules! common_cstring_impls {
       pub fn capacity<T: TryFrom<usize> + Default>(&self) -> T {
         self.capacity_usize().try_into().unwrap_or_default()
       }
+      /// Sets the length of the string and writes nul-terminator after it
+      ///
+      /// # Safety
+      ///
+      /// `len` should not exceed `capacity_usize` and first `len` characters of the buffer
+      /// should be initialized and not contain nul, e.g. written by C code through `as_mut_ptr`
+      pub unsafe fn set_len(&mut self, len: usize) {
+        debug_assert!(len <= self.capacity_usize());
+        self.0.as_mut_ptr().add(len).write(0);
+        self.0.set_len(len + 1);
+      }
+      /// Sets the length of the string to the position of the first nul character in the buffer
+      /// NOTE: the length is set to `capacity_usize` if nul character is not found
+      ///
+      /// # Safety
+      ///
+      /// all characters of the buffer before the first nul character (or within `capacity_usize`
+      /// if there is none) should be initialized, e.g. by C code writing through `as_mut_ptr`
+      pub unsafe fn update_len(&mut self) {
+        let data = self.0.as_ptr();
+        let mut len = 0;
+        while len < self.capacity_usize() && *data.add(len) != 0 {
+          len += 1;
+        }
+        self.set_len(len);
+      }
+      /// Returns the spare capacity of the buffer starting at the position of nul-terminator, i.e. at `len_usize`,
+      /// characters written to it become part of the string after `set_len(len_usize() + written)`
+      /// NOTE: room for the new nul-terminator is not included
+      pub fn spare_capacity_mut(&mut self) -> &mut [core::mem::MaybeUninit<$type>] {
+        let len = self.len_usize();
+        let spare = self.capacity_usize() - len;
+        // SAFETY: the range lies within the allocation and overwriting initialized characters is allowed for Copy types
+        unsafe { core::slice::from_raw_parts_mut(self.0.as_mut_ptr().add(len).cast(), spare) }
+      }
       pub fn as_slice(&self) -> &[$type] {
         let len = self.len_usize();
         &self.0[0..len]
       }
+      /// Returns the contents (without nul-terminator) as mutable slice
+      ///
+      /// # Safety
+      ///
+      /// nul characters should not be written to the slice, since the stored length is not updated,
+      /// consider using `as_mut_str` instead
       pub unsafe fn as_mut_slice(&mut self) -> &mut [$type] {
         let len = self.len_usize();
         &mut self.0[0..len]
@@ -1071,23 +1607,43 @@ macro_rules! common_cstring_impls {
         let len = self.len_with_nul_usize();
         &self.0[0..len]
       }
+      /// Returns the contents and nul-terminator as mutable slice
+      ///
+      /// # Safety
+      ///
+      /// nul characters should not be written to the contents and the last character,
+      /// i.e. nul-terminator, should not be altered, since the stored length is not updated
       pub unsafe fn as_mut_slice_with_nul(&mut self) -> &mut [$type] {
-        let len = self.len_with_nul_usize();
-        &mut self.0[0..len]
+        &mut self.0[..]
       }
+      /// Returns the initialized part of the buffer, i.e. contents and nul-terminator
+      /// NOTE: spare capacity is not initialized, see `spare_capacity_mut`
       pub fn as_slice_full(&self) -> &[$type] {
         &self.0
       }
+      /// Returns the initialized part of the buffer excluding nul-terminator
+      ///
+      /// # Safety
+      ///
+      /// same as for `as_mut_slice`, nul characters should not be written to the slice
       pub unsafe fn as_mut_slice_full(&mut self) -> &mut [$type] {
-        let len = self.0.len() - 1;
-        &mut self.0[0..len]
+        self.as_mut_slice()
       }
       pub fn as_ptr(&self) -> *const $type {
         self.0.as_ptr()
       }
+      /// Returns a mutable pointer to the buffer
+      /// NOTE: the stored length is not updated by writes through the pointer,
+      /// call `set_len` or `update_len` afterwards
       pub fn as_mut_ptr(&mut self) -> *mut $type {
         self.0.as_mut_ptr()
       }
+      /// Borrows the contents mutably
+      /// NOTE: the length is recalculated when the returned guard is dropped,
+      /// so nul characters written through it end the string at their position
+      pub fn as_mut_str(&mut self) -> $guard<'_> {
+        $guard(self)
+      }
       /// Returns the character at a given index
       pub fn get(&self, index: usize) -> Option<$type> {
         self.0.get(index).copied()
@@ -1096,38 +1652,38 @@ macro_rules! common_cstring_impls {
       pub fn get_ref(&self, index: usize) -> Option<&$type> {
         self.0.get(index)
       }
-      /// Returns mutable reference to the character at a given index
-      pub fn get_mut(&mut self, index: usize) -> Option<&mut $type> {
-        let len = self.0.len() - 1;
-        self.0[0..len].get_mut(index)
-      }
       /// Returns the substring of the string
       pub fn range(&self, range: core::ops::RangeFrom<usize>) -> &$asref {
-        unsafe { std::mem::transmute(&self.0[range]) }
-      }
-      /// Returns the mutable substring of the static str
-      pub fn range_mut(&mut self, range: core::ops::RangeFrom<usize>) -> &mut $asref {
-        let len = self.0.len() - 1;
-        unsafe { std::mem::transmute(&mut self.0[0..len][range]) }
+        unsafe { core::mem::transmute(&self.0[range]) }
       }
+      /// Copies data from slice appending nul-terminator
+      /// NOTE: contents after the first nul character are dropped,
+      /// consider using `try_from_slice` to reject such input
       pub fn from_slice(data: &[$type]) -> Self {
-        let mut buf = data.to_vec();
-        if let Some(&0) = data.last() {
-          Self(buf)
-        } else {
-          buf.push(0);
-          Self(buf)
-        }
+        let len = $find_nul(data).unwrap_or(data.len());
+        let mut buf = Vec::with_capacity(len + 1);
+        buf.extend_from_slice(&data[..len]);
+        buf.push(0);
+        Self(buf)
+      }
+      /// Copies data from slice appending nul-terminator if it is missing
+      /// NOTE: this function returns `StrError::InteriorNul` in case of nul character is found
+      /// anywhere but at the last position of `data`
+      pub fn try_from_slice(data: &[$type]) -> Result<Self, $crate::strings::StrError> {
+        let data = match data {
+          [rest @ .., 0] => rest,
+          data => data,
+        };
+        $crate::strings::internals::check_no_nul(data)?;
+        Ok(Self::from_slice(data))
       }
       pub unsafe fn from_ptr(data: *const $type) -> Self {
-        let inf_buf = core::slice::from_raw_parts(data, usize::MAX);
-        let len = inf_buf.iter().take_while(|c| **c != 0).count();
+        let len = <$type as $crate::strings::internals::NulScan>::scan_ptr(data);
         let buf = core::slice::from_raw_parts(data, len + 1).to_vec();
         Self(buf)
       }
       pub unsafe fn from_ptr_unchecked(data: *const $type, capacity: usize) -> Self {
-        let buf = core::slice::from_raw_parts(data, capacity).to_vec();
-        Self(buf)
+        Self::from_slice(core::slice::from_raw_parts(data, capacity))
       }
       pub unsafe fn from_ptr_n(
         data: *const $type,
@@ -1157,6 +1713,7 @@ macro_rules! common_cstring_impls {
       pub fn display<'a>(&'a self) -> $display<'a> {
         self.as_ref().display()
       }
+      /// Returns the underlying vector holding contents and nul-terminator
       pub fn into_inner(self) -> Vec<$type> {
         self.0
       }
@@ -1168,16 +1725,81 @@ macro_rules! common_cstring_impls {
       }
       /// Returns an iterator over characters of the static str
       /// until nul-terminator
-      /// NOTE: Items are returned by mutable reference
-      pub fn iter_mut(&mut self) -> $iter<&mut $name> {
-        $iter(self, 0)
-      }
-      /// Returns an iterator over characters of the static str
-      /// until nul-terminator
       /// NOTE: Items are returned by value
       pub fn into_iter(self) -> $iter<$name> {
         $iter(self, 0)
       }
+      /// Provides access to the contents (without nul-terminator) as vector
+      /// and restores nul-terminator afterwards
+      /// NOTE: `f` should not add nul characters to the vector
+      fn modify<R>(&mut self, f: impl FnOnce(&mut Vec<$type>) -> R) -> R {
+        self.0.pop();
+        let result = f(&mut self.0);
+        self.0.push(0);
+        result
+      }
+      /// Appends a character to the end of the string
+      /// NOTE: appending nul character ends the string at its position
+      pub fn push(&mut self, ch: $type) {
+        self.push_slice(&[ch])
+      }
+      /// Appends characters to the end of the string
+      /// NOTE: appending nul character ends the string at its position
+      pub fn push_slice(&mut self, data: &[$type]) {
+        let data = &data[..$find_nul(data).unwrap_or(data.len())];
+        self.modify(|buf| buf.extend_from_slice(data))
+      }
+      /// Appends contents of `data` to the end of the string
+      pub fn push_str(&mut self, data: &$asref) {
+        self.push_slice(data.as_slice())
+      }
+      /// Encodes `ch` and appends it to the end of the string
+      /// NOTE: this method returns Err in case of `ch` is unrepresentable
+      pub fn push_char(&mut self, ch: char) -> Result<(), $crate::strings::StrError> {
+        let encoded = $encode(ch.encode_utf8(&mut [0; 4]))?;
+        self.push_slice(&encoded);
+        Ok(())
+      }
+      /// Inserts a character at position `index`
+      /// NOTE: panics if `index` is greater then the length of the string
+      pub fn insert(&mut self, index: usize, ch: $type) {
+        self.insert_slice(index, &[ch])
+      }
+      /// Inserts contents of `data` at position `index`
+      /// NOTE: panics if `index` is greater then the length of the string
+      pub fn insert_str(&mut self, index: usize, data: &$asref) {
+        self.insert_slice(index, data.as_slice())
+      }
+      /// Inserts characters at position `index`
+      /// NOTE: characters starting from the first nul character are not inserted
+      /// NOTE: panics if `index` is greater then the length of the string
+      pub fn insert_slice(&mut self, index: usize, data: &[$type]) {
+        let data = &data[..$find_nul(data).unwrap_or(data.len())];
+        self.modify(|buf| {
+          assert!(index <= buf.len(), "insertion index (is {}) should be <= len (is {})", index, buf.len());
+          buf.splice(index..index, data.iter().copied());
+        })
+      }
+      /// Removes the character at position `index` and returns it
+      /// NOTE: panics if `index` is out of bounds of the string
+      pub fn remove(&mut self, index: usize) -> $type {
+        self.modify(|buf| {
+          assert!(index < buf.len(), "removal index (is {}) should be < len (is {})", index, buf.len());
+          buf.remove(index)
+        })
+      }
+      /// Removes the last character and returns it
+      pub fn pop(&mut self) -> Option<$type> {
+        self.modify(|buf| buf.pop())
+      }
+      /// Retains only the characters specified by the predicate
+      pub fn retain(&mut self, mut f: impl FnMut($type) -> bool) {
+        self.modify(|buf| buf.retain(|ch| f(*ch)))
+      }
+      /// Makes the string empty
+      pub fn clear(&mut self) {
+        self.modify(|buf| buf.clear())
+      }
     }
     impl From<&[$type]> for $name {
       fn from(value: &[$type]) -> Self {
@@ -1195,13 +1817,11 @@ macro_rules! common_cstring_impls {
       }
     }
     impl From<Vec<$type>> for $name {
+      /// NOTE: reuses the buffer, contents after the first nul character are dropped
       fn from(mut buf: Vec<$type>) -> Self {
-        if let Some(&0) = buf.last() {
-          Self(buf)
-        } else {
-          buf.push(0);
-          Self(buf)
-        }
+        buf.truncate($find_nul(&buf).unwrap_or(buf.len()));
+        buf.push(0);
+        Self(buf)
       }
     }
     impl AsRef<$asref> for $name {
@@ -1209,21 +1829,11 @@ macro_rules! common_cstring_impls {
         unsafe { <$asref>::from_slice_unchecked(&self.0) }
       }
     }
-    impl AsMut<$asref> for $name {
-      fn as_mut(&mut self) -> &mut $asref {
-        unsafe { <$asref>::from_mut_slice_unchecked(&mut self.0) }
-      }
-    }
     impl core::borrow::Borrow<$asref> for $name {
       fn borrow(&self) -> &$asref {
         self.as_ref()
       }
     }
-    impl core::borrow::BorrowMut<$asref> for $name {
-      fn borrow_mut(&mut self) -> &mut $asref {
-        self.as_mut()
-      }
-    }
     impl core::ops::Deref for $name {
       type Target = $asref;
 
@@ -1232,15 +1842,9 @@ macro_rules! common_cstring_impls {
         self.as_ref()
       }
     }
-    impl core::ops::DerefMut for $name {
-      #[inline]
-      fn deref_mut(&mut self) -> &mut Self::Target {
-        self.as_mut()
-      }
-    }
     impl Default for $name {
       fn default() -> Self {
-        Self::new()
+        Self::with_capacity(0)
       }
     }
     impl core::ops::Index<usize> for $name {
@@ -1250,12 +1854,6 @@ macro_rules! common_cstring_impls {
         self.get_ref(index).unwrap()
       }
     }
-    impl core::ops::IndexMut<usize> for $name {
-      #[inline]
-      fn index_mut(&mut self, index: usize) -> &mut Self::Output {
-        self.get_mut(index).unwrap()
-      }
-    }
     impl core::ops::Index<core::ops::RangeFrom<usize>> for $name {
       type Output = $asref;
       #[inline]
@@ -1263,13 +1861,32 @@ macro_rules! common_cstring_impls {
         self.range(index)
       }
     }
-    impl core::ops::IndexMut<core::ops::RangeFrom<usize>> for $name {
+    /// Mutable borrow of the contents of an owned string returned by `as_mut_str`
+    /// NOTE: the string is truncated at the first nul character when the guard is dropped
+    pub struct $guard<'a>(&'a mut $name);
+    impl core::ops::Deref for $guard<'_> {
+      type Target = $asref;
+
       #[inline]
-      fn index_mut(&mut self, index: core::ops::RangeFrom<usize>) -> &mut Self::Output {
-        self.range_mut(index)
+      fn deref(&self) -> &$asref {
+        self.0.as_ref()
       }
     }
-    /// A call to `$name::into_iter` or `$name::iter` or `$name::iter_mut` returns an instance of this class
+    impl core::ops::DerefMut for $guard<'_> {
+      #[inline]
+      fn deref_mut(&mut self) -> &mut $asref {
+        // SAFETY: the vector ends with nul-terminator and mutable cstr does not provide access to it
+        unsafe { <$asref>::from_mut_slice_unchecked(&mut (self.0).0) }
+      }
+    }
+    impl Drop for $guard<'_> {
+      fn drop(&mut self) {
+        let buf = &mut (self.0).0;
+        let len = $find_nul(buf).unwrap_or(buf.len() - 1);
+        buf.truncate(len + 1);
+      }
+    }
+    /// A call to `$name::into_iter` or `$name::iter` returns an instance of this class
     /// It can be used to iterate over characters of static str until nul-terminator
     pub struct $iter<T>(T, usize);
     impl core::iter::Iterator for $iter<$name> {
@@ -1294,17 +1911,6 @@ macro_rules! common_cstring_impls {
         Some(ret)
       }
     }
-    impl<'col> core::iter::Iterator for $iter<&'col mut $name> {
-      type Item = &'col mut $type;
-      fn next(&mut self) -> Option<<Self as Iterator>::Item> {
-        let ret = self.0.get_mut(self.1)?;
-        if *ret == 0 {
-          return None;
-        }
-        self.1 += 1;
-        Some(unsafe { &mut *(ret as *mut _) })
-      }
-    }
     impl core::iter::IntoIterator for $name {
       type Item = $type;
       type IntoIter = $iter<$name>;
@@ -1328,46 +1934,59 @@ macro_rules! common_cstring_impls {
       }
     }
     impl core::cmp::Eq for $name {}
+    impl core::cmp::PartialOrd for $name {
+      fn partial_cmp(&self, rhs: &$name) -> Option<core::cmp::Ordering> {
+        Some(core::cmp::Ord::cmp(self, rhs))
+      }
+    }
+    impl core::fmt::Write for $name {
+      /// Encodes `s` and appends it to the end of the string
+      /// NOTE: u8 strings are encoded as UTF-8, u16 as UTF-16 and u32 as UTF-32
+      /// NOTE: this method returns Err in case of `s` contains nul
+      fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        use $crate::strings::internals::FmtUnit;
+        if s.contains('\0') {
+          return Err(core::fmt::Error);
+        }
+        self.modify(|buf| buf.extend(<$type as FmtUnit>::encode_str(s)));
+        Ok(())
+      }
+    }
+    #[cfg(feature = "std")]
+    impl $crate::strings::CStrSink for $name {
+      type Char = $type;
+      fn push_units(&mut self, units: &[$type]) -> bool {
+        self.push_slice(units);
+        true
+      }
+    }
+    common_cmp_impls!([] $name, $type);
+    common_debug_impl!([] $name, $type, $name, |this| this.capacity_usize());
   };
 }
 
 macro_rules! common_owningcstr_impls {
   ($name:ident, $type:ty, $into:ty, $asref:ty, $display:ident, $iter:ident) => {
-    #[derive(Debug)]
-    pub struct $name<DELETER: FnOnce(*mut $type)> {
-      data: *mut $type,
-      deleter: Option<DELETER>,
-    }
-    impl<DELETER: FnOnce(*mut $type)> $crate::strings::CStrCharType for $name<DELETER> {
+    #[doc = concat!("Owned C string of `", stringify!($type), "` characters freed by `DELETER` on drop")]
+    pub type $name<DELETER = $crate::strings::LibcFree> = $crate::strings::OwningCStr<$type, DELETER>;
+    impl<DELETER: $crate::strings::CDeleter<$type>> $crate::strings::CStrCharType for $name<DELETER> {
       type Char = $type;
     }
-    impl<DELETER: FnOnce(*mut $type)> $name<DELETER> {
+    impl<DELETER: $crate::strings::CDeleter<$type>> $name<DELETER> {
       /// Calculates the length of str by iterating over it's contents
       /// searching for nul-terminator character
-      pub const fn len_usize(&self) -> usize {
-        let mut items = unsafe { ::std::slice::from_raw_parts(self.data, usize::MAX) };
-        let mut i = 0;
-        while let Some(item) = items.first() {
-          if *item == 0 {
-            return i;
-          }
-          items = match items {
-            [_, rest @ ..] => rest,
-            [] => break,
-          };
-          i += 1
-        }
-        i
+      pub fn len_usize(&self) -> usize {
+        unsafe { <$type as $crate::strings::internals::NulScan>::scan_ptr(self.data) }
       }
       /// Checks wheither the str is empty
       /// i.e. starts with nul-terminator character
-      pub const fn is_empty(&self) -> bool {
+      pub fn is_empty(&self) -> bool {
         self.len_usize() == 0
       }
       /// Calculates the length of str by iterating over it's contents
       /// searching for nul-terminator character.
       /// NOTE: This method returns the result as DWORD
-      pub const fn len_dword(&self) -> u32 {
+      pub fn len_dword(&self) -> u32 {
         self.len_usize() as u32
       }
       /// Calculates the length of str by iterating over it's contents
@@ -1393,12 +2012,12 @@ macro_rules! common_owningcstr_impls {
           .unwrap_or_default()
       }
       /// Calculates the length of the str including nul-terminator character.
-      pub const fn len_with_nul_usize(&self) -> usize {
+      pub fn len_with_nul_usize(&self) -> usize {
         self.len_usize() + 1
       }
       /// Calculates the length of the str including nul-terminator character.
       /// NOTE: This method casts the result to DWORD
-      pub const fn len_with_nul_dword(&self) -> u32 {
+      pub fn len_with_nul_dword(&self) -> u32 {
         self.len_dword() + 1
       }
       /// Calculates the length of the str including nul-terminator character.
@@ -1407,17 +2026,17 @@ macro_rules! common_owningcstr_impls {
         (self.len_usize() + 1).try_into().unwrap_or_default()
       }
       /// Returns the contents of the str until nul-terminator (not including) as immutable slice
-      pub const fn as_slice(&self) -> &[$type] {
+      pub fn as_slice(&self) -> &[$type] {
         // Const implementation of: "&self.0[0..self.len_usize()]""
         unsafe { core::slice::from_raw_parts(self.data, self.len_usize()) }
       }
       /// Returns the contents of the str until nul-terminator (not including) as mutable slice
       pub fn as_mut_slice(&mut self) -> &mut [$type] {
         let len = self.len_usize();
-        &mut self.0[0..len]
+        unsafe { core::slice::from_raw_parts_mut(self.data, len) }
       }
       /// Returns the contents of the str until nul-terminator (and including it) as immutable slice
-      pub const fn as_slice_with_nul(&self) -> &[$type] {
+      pub fn as_slice_with_nul(&self) -> &[$type] {
         // Const implementation of: "&self.0[0..self.len_with_nul_usize()]"
         unsafe { core::slice::from_raw_parts(self.data, self.len_with_nul_usize()) }
       }
@@ -1425,7 +2044,7 @@ macro_rules! common_owningcstr_impls {
       /// SAFETY: caller should not mutate the nul-termianator
       pub unsafe fn as_mut_slice_with_nul(&mut self) -> &mut [$type] {
         let len = self.len_with_nul_usize();
-        core::slice::from_raw_parts_mut(self.0.as_mut_ptr(), len)
+        core::slice::from_raw_parts_mut(self.data, len)
       }
       /// Returns the const pointer to the contents
       /// NOTE: string represented by the returned pointer is always nul-terminated
@@ -1445,14 +2064,14 @@ macro_rules! common_owningcstr_impls {
         self.data
       }
       /// Returns the character at a given index
-      pub const fn get(&self, index: usize) -> Option<$type> {
+      pub fn get(&self, index: usize) -> Option<$type> {
         if index >= self.len_usize() {
           return None;
         }
         Some(unsafe { *(self.data.add(index) as *const _) })
       }
       /// Returns the character at a given index
-      pub const fn get_ref(&self, index: usize) -> Option<&$type> {
+      pub fn get_ref(&self, index: usize) -> Option<&$type> {
         if index >= self.len_usize() {
           return None;
         }
@@ -1461,14 +2080,14 @@ macro_rules! common_owningcstr_impls {
       /// Returns mutable reference to the character at a given index
       /// NOTE: this method returns None in case of `index` beeing greater then or equal to `CAPACITY`
       pub fn get_mut(&mut self, index: usize) -> Option<&mut $type> {
-        unsafe { ::std::slice::from_raw_parts_mut(self.data, self.len_usize()).get_mut(index) }
+        unsafe { ::core::slice::from_raw_parts_mut(self.data, self.len_usize()).get_mut(index) }
       }
       /// Returns the substring of the str
-      pub const fn range(&self, range: core::ops::RangeFrom<usize>) -> &$asref {
+      pub fn range(&self, range: core::ops::RangeFrom<usize>) -> &$asref {
         let len = self.len_usize();
         if range.start >= len {
           unsafe {
-            <$asref>::from_slice_unchecked(::std::slice::from_raw_parts(self.data.add(len), len))
+            <$asref>::from_slice_unchecked(::core::slice::from_raw_parts(self.data.add(len), len))
           }
         } else {
           unsafe { <$asref>::from_ptr_unchecked(self.data.add(range.start), len + 1 - range.start) }
@@ -1479,7 +2098,7 @@ macro_rules! common_owningcstr_impls {
         let len = self.len_usize();
         if range.start >= len {
           unsafe {
-            <$asref>::from_mut_slice_unchecked(::std::slice::from_raw_parts_mut(
+            <$asref>::from_mut_slice_unchecked(::core::slice::from_raw_parts_mut(
               self.data.add(len),
               len,
             ))
@@ -1491,15 +2110,16 @@ macro_rules! common_owningcstr_impls {
         }
       }
       /// Ownes the string pointed to by data
+      /// SAFETY: `data` should point to a valid nul-terminated string that can be freed by `deleter`
       pub const unsafe fn from_ptr_safe_deleter(data: *mut $type, deleter: DELETER) -> Self {
         Self {
           data,
-          deleter: Some(deleter),
+          deleter: core::mem::ManuallyDrop::new(deleter),
         }
       }
       /// Returns display wrapper for static str
       pub fn display<'a>(&'a self) -> $display<'a> {
-        $display(unsafe { ::std::slice::from_raw_parts(self.data, self.len_usize()) })
+        $display(unsafe { ::core::slice::from_raw_parts(self.data, self.len_usize()) })
       }
       /// Returns an iterator over characters of the static str
       /// until nul-terminator
@@ -1522,76 +2142,125 @@ macro_rules! common_owningcstr_impls {
         let len = self.len_usize();
         $iter(self, 0, len)
       }
-    }
-    impl $name<fn(*mut $type)> {
-      /// Ownes the string pointed to by data
-      pub const unsafe fn from_ptr<T>(
+      /// Releases ownership of the string and returns the pointer to it
+      /// NOTE: the deleter is dropped without being called, so the caller is responsible for freeing the string
+      pub fn into_raw(self) -> *mut $type {
+        let mut this = core::mem::ManuallyDrop::new(self);
+        unsafe { core::mem::ManuallyDrop::drop(&mut this.deleter) };
+        this.data
+      }
+    }
+    impl<T> $name<$crate::strings::ExternCDeleter<T>> {
+      /// Ownes the string pointed to by data, the string is freed by calling `deleter` on drop
+      ///
+      /// # Safety
+      ///
+      /// `data` should point to a valid nul-terminated string that can be freed by `deleter`,
+      /// the string should not be freed or accessed elsewhere while it is owned
+      pub const unsafe fn from_ptr(
         data: *mut $type,
         deleter: unsafe extern "C" fn(*mut T),
       ) -> Self {
-        Self {
-          data,
-          deleter: Some(core::mem::transmute(deleter)),
-        }
-      }
-      pub const unsafe fn new(data: *mut $type) -> Self {
-        extern "C" {
-          fn free(ptr: *mut ::core::ffi::c_void);
-        }
-        let deleter: unsafe extern "C" fn(*mut ::core::ffi::c_void) = free;
-        Self {
-          data,
-          deleter: Some(core::mem::transmute(deleter))
-        }
+        Self::from_ptr_safe_deleter(data, $crate::strings::ExternCDeleter(deleter))
       }
     }
-    impl<DELETER: Default + FnOnce(*mut $type)> $name<DELETER> {
-      pub fn from_ptr_default(data: *mut $type) -> Self {
-        Self {
-          data,
-          deleter: Default::default(),
-        }
-      }
-    }
-    impl<DELETER: FnOnce(*mut $type)> From<$name<DELETER>> for $into {
+    #[cfg(feature = "libc")]
+    impl $name<$crate::strings::LibcFree> {
+      /// Ownes the string pointed to by data, the string is freed by `free()` of C runtime
+      /// SAFETY: `data` should point to a valid nul-terminated string allocated by `malloc`
+      pub const unsafe fn new(data: *mut $type) -> Self {
+        Self::from_ptr_safe_deleter(data, $crate::strings::LibcFree)
+      }
+      /// Copies `src` into memory allocated by `malloc`,
+      /// so the returned string can be handed over to C code that frees it with `free()`
+      /// NOTE: calls `handle_alloc_error` in case of allocation failure, see `try_alloc_copy`
+      #[cfg(feature = "alloc")]
+      pub fn alloc_copy(src: &$asref) -> Self {
+        Self::try_alloc_copy(src)
+          .unwrap_or_else(|| $crate::strings::allocator::alloc_copy_error(src.as_slice_with_nul()))
+      }
+      /// Copies `src` into memory allocated by `malloc`
+      /// NOTE: returns None in case of allocation failure
+      pub fn try_alloc_copy(src: &$asref) -> Option<Self> {
+        let data = $crate::strings::allocator::alloc_copy_units(&$crate::strings::LibcAlloc, src.as_slice_with_nul())?;
+        Some(unsafe { Self::new(data) })
+      }
+    }
+    impl<A: $crate::strings::CAllocator> $name<A> {
+      /// Copies `src` into memory allocated by `allocator`, the string is freed by the same allocator on drop
+      /// NOTE: calls `handle_alloc_error` in case of allocation failure, see `try_alloc_copy_in`
+      #[cfg(feature = "alloc")]
+      pub fn alloc_copy_in(src: &$asref, allocator: A) -> Self {
+        Self::try_alloc_copy_in(src, allocator)
+          .unwrap_or_else(|| $crate::strings::allocator::alloc_copy_error(src.as_slice_with_nul()))
+      }
+      /// Copies `src` into memory allocated by `allocator`
+      /// NOTE: returns None in case of allocation failure
+      pub fn try_alloc_copy_in(src: &$asref, allocator: A) -> Option<Self> {
+        let data = $crate::strings::allocator::alloc_copy_units(&allocator, src.as_slice_with_nul())?;
+        Some(unsafe { Self::from_ptr_safe_deleter(data, allocator) })
+      }
+    }
+    #[cfg(all(feature = "alloc", feature = "libc"))]
+    impl core::str::FromStr for $name<$crate::strings::LibcFree> {
+      type Err = $crate::strings::StrError;
+      /// Encodes `s` into memory allocated by `malloc`
+      fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let string = <$into as $crate::strings::CStrCharType>::encode(s)?;
+        Ok(Self::alloc_copy(&string))
+      }
+    }
+    impl<DELETER: Default + $crate::strings::CDeleter<$type>> $name<DELETER> {
+      /// Ownes the string pointed to by data, the string is freed by default `DELETER` on drop
+      ///
+      /// # Safety
+      ///
+      /// `data` should point to a valid nul-terminated string that can be freed by default `DELETER`,
+      /// the string should not be freed or accessed elsewhere while it is owned
+      pub unsafe fn from_ptr_default(data: *mut $type) -> Self {
+        Self::from_ptr_safe_deleter(data, Default::default())
+      }
+    }
+    #[cfg(feature = "alloc")]
+    impl<DELETER: $crate::strings::CDeleter<$type>> From<$name<DELETER>> for $into {
       fn from(value: $name<DELETER>) -> Self {
         let len = value.len_usize();
-        Self::from(unsafe { std::slice::from_raw_parts(value.data, len + 1) })
+        Self::from(unsafe { core::slice::from_raw_parts(value.data, len + 1) })
       }
     }
-    impl<DELETER: FnOnce(*mut $type)> AsRef<$name<DELETER>> for &$name<DELETER> {
+    impl<DELETER: $crate::strings::CDeleter<$type>> AsRef<$name<DELETER>> for &$name<DELETER> {
       fn as_ref(&self) -> &$name<DELETER> {
         self
       }
     }
-    impl<DELETER: FnOnce(*mut $type)> AsMut<$name<DELETER>> for &mut $name<DELETER> {
+    impl<DELETER: $crate::strings::CDeleter<$type>> AsMut<$name<DELETER>> for &mut $name<DELETER> {
       fn as_mut(&mut self) -> &mut $name<DELETER> {
         self
       }
     }
-    impl<DELETER: FnOnce(*mut $type)> AsRef<$asref> for $name<DELETER> {
+    impl<DELETER: $crate::strings::CDeleter<$type>> AsRef<$asref> for $name<DELETER> {
       fn as_ref(&self) -> &$asref {
         let len = self.len_usize();
-        unsafe { <$asref>::from_ptr_unchecked(self.0.as_ptr(), len + 1) }
+        unsafe { <$asref>::from_ptr_unchecked(self.data, len + 1) }
       }
     }
-    impl<DELETER: FnOnce(*mut $type)> AsMut<$asref> for $name<DELETER> {
+    impl<DELETER: $crate::strings::CDeleter<$type>> AsMut<$asref> for $name<DELETER> {
       fn as_mut(&mut self) -> &mut $asref {
         let len = self.len_usize();
-        unsafe { <$asref>::from_mut_ptr_unchecked(self.0.as_mut_ptr(), len + 1) }
+        unsafe { <$asref>::from_mut_ptr_unchecked(self.data, len + 1) }
       }
     }
-    impl<DELETER: FnOnce(*mut $type)> core::borrow::Borrow<$asref> for $name<DELETER> {
+    impl<DELETER: $crate::strings::CDeleter<$type>> core::borrow::Borrow<$asref> for $name<DELETER> {
       fn borrow(&self) -> &$asref {
         self.as_ref()
       }
     }
-    impl<DELETER: FnOnce(*mut $type)> core::borrow::BorrowMut<$asref> for $name<DELETER> {
+    impl<DELETER: $crate::strings::CDeleter<$type>> core::borrow::BorrowMut<$asref> for $name<DELETER> {
       fn borrow_mut(&mut self) -> &mut $asref {
         self.as_mut()
       }
     }
-    impl<DELETER: FnOnce(*mut $type)> core::ops::Deref for $name<DELETER> {
+    impl<DELETER: $crate::strings::CDeleter<$type>> core::ops::Deref for $name<DELETER> {
       type Target = $asref;
 
       #[inline]
@@ -1599,26 +2268,26 @@ macro_rules! common_owningcstr_impls {
         self.as_ref()
       }
     }
-    impl<DELETER: FnOnce(*mut $type)> core::ops::DerefMut for $name<DELETER> {
+    impl<DELETER: $crate::strings::CDeleter<$type>> core::ops::DerefMut for $name<DELETER> {
       #[inline]
       fn deref_mut(&mut self) -> &mut Self::Target {
         self.as_mut()
       }
     }
-    impl<DELETER: FnOnce(*mut $type)> core::ops::Index<usize> for $name<DELETER> {
+    impl<DELETER: $crate::strings::CDeleter<$type>> core::ops::Index<usize> for $name<DELETER> {
       type Output = $type;
       #[inline]
       fn index(&self, index: usize) -> &Self::Output {
         self.get_ref(index).unwrap()
       }
     }
-    impl<DELETER: FnOnce(*mut $type)> core::ops::IndexMut<usize> for $name<DELETER> {
+    impl<DELETER: $crate::strings::CDeleter<$type>> core::ops::IndexMut<usize> for $name<DELETER> {
       #[inline]
       fn index_mut(&mut self, index: usize) -> &mut Self::Output {
         self.get_mut(index).unwrap()
       }
     }
-    impl<DELETER: FnOnce(*mut $type)> core::ops::Index<core::ops::RangeFrom<usize>>
+    impl<DELETER: $crate::strings::CDeleter<$type>> core::ops::Index<core::ops::RangeFrom<usize>>
       for $name<DELETER>
     {
       type Output = $asref;
@@ -1627,7 +2296,7 @@ macro_rules! common_owningcstr_impls {
         self.range(index)
       }
     }
-    impl<DELETER: FnOnce(*mut $type)> core::ops::IndexMut<core::ops::RangeFrom<usize>>
+    impl<DELETER: $crate::strings::CDeleter<$type>> core::ops::IndexMut<core::ops::RangeFrom<usize>>
       for $name<DELETER>
     {
       #[inline]
@@ -1635,23 +2304,13 @@ macro_rules! common_owningcstr_impls {
         self.range_mut(index)
       }
     }
-    // impl<DELETER: FnOnce(*mut $type)> Drop for $name<DELETER> {
-    //   fn drop(&mut self) {
-    //     self.deleter(self.data)
-    //   }
-    // }
-    impl<DELETER: FnOnce(*mut $type)> Drop for $name<DELETER> {
-      fn drop(&mut self) {
-        (self.deleter.take().unwrap())(self.data)
-      }
-    }
     /// A call to `$name::into_iter` or `$name::iter` or `$name::iter_mut` returns an instance of this class
     /// It can be used to iterate over characters of str until nul-terminator
     pub struct $iter<T>(T, usize, usize);
-    impl<DELETER: FnOnce(*mut $type)> core::iter::Iterator for $iter<$name<DELETER>> {
+    impl<DELETER: $crate::strings::CDeleter<$type>> core::iter::Iterator for $iter<$name<DELETER>> {
       type Item = $type;
       fn next(&mut self) -> Option<<Self as Iterator>::Item> {
-        let ret = unsafe { ::std::slice::from_raw_parts(self.0.data, self.2) }.get(self.1)?;
+        let ret = unsafe { ::core::slice::from_raw_parts(self.0.data, self.2) }.get(self.1)?;
         if *ret == 0 {
           return None;
         }
@@ -1659,10 +2318,10 @@ macro_rules! common_owningcstr_impls {
         Some(*ret)
       }
     }
-    impl<'col, DELETER: FnOnce(*mut $type)> core::iter::Iterator for $iter<&'col $name<DELETER>> {
+    impl<'col, DELETER: $crate::strings::CDeleter<$type>> core::iter::Iterator for $iter<&'col $name<DELETER>> {
       type Item = &'col $type;
       fn next(&mut self) -> Option<<Self as Iterator>::Item> {
-        let ret = unsafe { ::std::slice::from_raw_parts(self.0.data, self.2) }.get(self.1)?;
+        let ret = unsafe { ::core::slice::from_raw_parts(self.0.data, self.2) }.get(self.1)?;
         if *ret == 0 {
           return None;
         }
@@ -1670,13 +2329,13 @@ macro_rules! common_owningcstr_impls {
         Some(ret)
       }
     }
-    impl<'col, DELETER: FnOnce(*mut $type)> core::iter::Iterator
+    impl<'col, DELETER: $crate::strings::CDeleter<$type>> core::iter::Iterator
       for $iter<&'col mut $name<DELETER>>
     {
       type Item = &'col mut $type;
       fn next(&mut self) -> Option<<Self as Iterator>::Item> {
         let ret =
-          unsafe { ::std::slice::from_raw_parts_mut(self.0.data, self.2) }.get_mut(self.1)?;
+          unsafe { ::core::slice::from_raw_parts_mut(self.0.data, self.2) }.get_mut(self.1)?;
         if *ret == 0 {
           return None;
         }
@@ -1684,14 +2343,14 @@ macro_rules! common_owningcstr_impls {
         Some(unsafe { &mut *(ret as *mut _) })
       }
     }
-    impl<DELETER: FnOnce(*mut $type)> core::iter::IntoIterator for $name<DELETER> {
+    impl<DELETER: $crate::strings::CDeleter<$type>> core::iter::IntoIterator for $name<DELETER> {
       type Item = $type;
       type IntoIter = $iter<$name<DELETER>>;
       fn into_iter(self) -> Self::IntoIter {
         self.into_iter()
       }
     }
-    impl<DEL1: FnOnce(*mut $type), DEL2: FnOnce(*mut $type)> core::cmp::PartialEq<$name<DEL1>>
+    impl<DEL1: $crate::strings::CDeleter<$type>, DEL2: $crate::strings::CDeleter<$type>> core::cmp::PartialEq<$name<DEL1>>
       for $name<DEL2>
     {
       fn eq(&self, rhs: &$name<DEL1>) -> bool {
@@ -1708,39 +2367,44 @@ macro_rules! common_owningcstr_impls {
         first.zip(second).all(|(a, b)| a == b)
       }
     }
-    impl<DEL: FnOnce(*mut $type)> core::cmp::Eq for $name<DEL> {}
+    impl<DEL: $crate::strings::CDeleter<$type>> core::cmp::Eq for $name<DEL> {}
+    impl<DEL1: $crate::strings::CDeleter<$type>, DEL2: $crate::strings::CDeleter<$type>> core::cmp::PartialOrd<$name<DEL1>>
+      for $name<DEL2>
+    {
+      fn partial_cmp(&self, rhs: &$name<DEL1>) -> Option<core::cmp::Ordering> {
+        Some(self.as_slice().cmp(rhs.as_slice()))
+      }
+    }
+    common_cmp_impls!([DEL: $crate::strings::CDeleter<$type>] $name<DEL>, $type);
+    common_debug_impl!([DEL: $crate::strings::CDeleter<$type>] $name<DEL>, $type, $name, |this| this.len_usize());
+    impl<DEL: $crate::strings::CDeleter<$type>> core::cmp::PartialEq<$asref> for $name<DEL> {
+      fn eq(&self, rhs: &$asref) -> bool {
+        self.as_slice() == rhs.as_slice()
+      }
+    }
+    impl<DEL: $crate::strings::CDeleter<$type>> core::cmp::PartialEq<$name<DEL>> for $asref {
+      fn eq(&self, rhs: &$name<DEL>) -> bool {
+        self.as_slice() == rhs.as_slice()
+      }
+    }
+    #[cfg(feature = "alloc")]
+    impl<DEL: $crate::strings::CDeleter<$type>> core::cmp::PartialEq<$into> for $name<DEL> {
+      fn eq(&self, rhs: &$into) -> bool {
+        self.as_slice() == rhs.as_slice()
+      }
+    }
+    #[cfg(feature = "alloc")]
+    impl<DEL: $crate::strings::CDeleter<$type>> core::cmp::PartialEq<$name<DEL>> for $into {
+      fn eq(&self, rhs: &$name<DEL>) -> bool {
+        self.as_slice() == rhs.as_slice()
+      }
+    }
   };
 }
 
 macro_rules! common_staticstr_writes_impl {
   ($name:ty, $fn:ident) => {
-    impl<const CAPACITY: usize> core::fmt::Write for $name {
-      fn write_str(&mut self, s: &str) -> core::fmt::Result {
-        let mut buf = s.as_bytes();
-        let prev_len = self.len_usize();
-        if buf.is_empty() {
-          return Ok(());
-        }
-        let chars_len = unsafe { $crate::strings::internals::$fn(buf) }
-          .ok()
-          .ok_or(core::fmt::Error)?;
-        if chars_len > CAPACITY - prev_len - 1 {
-          return Err(core::fmt::Error);
-        }
-        let buffer = &mut self.0[prev_len..prev_len + chars_len];
-        for i in 0..chars_len {
-          let (cp, rest) = unsafe { $crate::strings::internals::next_code_point(buf).unwrap() };
-          if cp > <$name as crate::strings::CStrCharType>::Char::MAX as u32 {
-            return Err(core::fmt::Error);
-          }
-          buffer[i] = cp as <$name as crate::strings::CStrCharType>::Char;
-          buf = rest;
-        }
-        self.0[CAPACITY - 1] = 0;
-        Ok(())
-      }
-    }
-    #[cfg(not(feature = "no_std"))]
+    #[cfg(feature = "std")]
     impl<const CAPACITY: usize> std::io::Write for $name {
       fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
         let prev_len = self.len_usize();
@@ -1783,31 +2447,7 @@ macro_rules! common_staticstr_writes_impl {
 
 macro_rules! common_str_writes_impl {
   ($name:ty, $fn:ident) => {
-    impl core::fmt::Write for &mut $name {
-      fn write_str(&mut self, s: &str) -> core::fmt::Result {
-        let mut buf = s.as_bytes();
-        let chars_len = unsafe { $crate::strings::internals::$fn(buf) }
-          .ok()
-          .ok_or(core::fmt::Error)?;
-        let writable = self.capacity_usize();
-        if chars_len > writable {
-          return Err(core::fmt::Error);
-        }
-        type CharType = <$name as $crate::strings::CStrCharType>::Char;
-        for i in 0..chars_len {
-          let (cp, rest) = unsafe { $crate::strings::internals::next_code_point(buf).unwrap() };
-          if cp > CharType::MAX as u32 {
-            return Err(core::fmt::Error);
-          }
-          self.0[i] = cp as CharType;
-          buf = rest;
-        }
-        self.0[chars_len] = 0;
-        *self = unsafe { core::mem::transmute(&mut self.0[chars_len..]) };
-        Ok(())
-      }
-    }
-    #[cfg(not(feature = "no_std"))]
+    #[cfg(feature = "std")]
     impl std::io::Write for &mut $name {
       fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
         let valid_bytes = if let Err(err) = $crate::strings::internals::check_is_valid_utf8(buf) {
@@ -1844,39 +2484,12 @@ macro_rules! common_str_writes_impl {
   };
 }
 
+#[cfg(feature = "alloc")]
 macro_rules! common_string_writes_impl {
   ($name:ty, $fn:ident) => {
-    impl core::fmt::Write for &mut $name {
-      fn write_str(&mut self, s: &str) -> core::fmt::Result {
-        let prev_len = self.len_usize();
-        let mut buf = s.as_bytes();
-        if buf.is_empty() {
-          return Ok(());
-        }
-        let chars_len = unsafe { $crate::strings::internals::$fn(buf) }
-          .ok()
-          .ok_or(core::fmt::Error)?;
-        self.0.resize(chars_len + prev_len + 1, 0);
-        let buffer = &mut self.0[prev_len..prev_len + chars_len];
-        type CharType = <$name as $crate::strings::CStrCharType>::Char;
-        for i in 0..chars_len {
-          let (cp, rest) = unsafe { $crate::strings::internals::next_code_point(buf).unwrap() };
-          if cp > CharType::MAX as u32 {
-            return Err(core::fmt::Error);
-          }
-          buffer[i] = cp as CharType;
-          buf = rest;
-        }
-        self.0[prev_len + chars_len] = 0;
-        let cap = self.0.capacity();
-        self.0.resize(cap, 0);
-        Ok(())
-      }
-    }
-    #[cfg(not(feature = "no_std"))]
+    #[cfg(feature = "std")]
     impl std::io::Write for $name {
       fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
-        let prev_len = self.len_usize();
         let valid_bytes = if let Err(err) = $crate::strings::internals::check_is_valid_utf8(buf) {
           err.valid_up_to()
         } else {
@@ -1888,24 +2501,26 @@ macro_rules! common_string_writes_impl {
         }
         use $crate::ignore::ResultIgnoreExt;
         let chars_len = unsafe { $crate::strings::internals::$fn(buf) }.ignore();
-        self.0.resize(chars_len + prev_len + 1, 0);
-        let buffer = &mut self.0[prev_len..prev_len + chars_len];
         type CharType = <$name as $crate::strings::CStrCharType>::Char;
-        for i in 0..chars_len {
-          let (cp, rest) = unsafe { $crate::strings::internals::next_code_point(buf).unwrap() };
-          if cp > CharType::MAX as u32 {
-            return Err(std::io::Error::new(
-              std::io::ErrorKind::InvalidData,
-              "input buffer contained character that is unrepresentable in target encoding",
-            ));
+        self.modify(|data| {
+          data.reserve(chars_len);
+          for _ in 0..chars_len {
+            let (cp, rest) = unsafe { $crate::strings::internals::next_code_point(buf).unwrap() };
+            if cp > CharType::MAX as u32 {
+              return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "input buffer contained character that is unrepresentable in target encoding",
+              ));
+            }
+            // nul character ends the string at its position
+            if cp == 0 {
+              break;
+            }
+            data.push(cp as CharType);
+            buf = rest;
           }
-          buffer[i] = cp as CharType;
-          buf = rest;
-        }
-        self.0[prev_len + chars_len] = 0;
-        let cap = self.0.capacity();
-        self.0.resize(cap, 0);
-        Ok(valid_bytes)
+          Ok(valid_bytes)
+        })
       }
 
       fn flush(&mut self) -> std::io::Result<()> {
@@ -1915,10 +2530,14 @@ macro_rules! common_string_writes_impl {
   };
 }
 
+pub(super) use common_cmp_impls;
 pub(super) use common_cstr_impls;
+pub(super) use common_debug_impl;
+#[cfg(feature = "alloc")]
 pub(super) use common_cstring_impls;
 pub(super) use common_owningcstr_impls;
 pub(super) use common_staticcstr_impls;
 pub(super) use common_staticstr_writes_impl;
 pub(super) use common_str_writes_impl;
+#[cfg(feature = "alloc")]
 pub(super) use common_string_writes_impl;
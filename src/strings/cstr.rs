@@ -1,47 +1,121 @@
-use super::{common::{common_cstr_impls, common_cstring_impls, common_staticcstr_impls, common_owningcstr_impls}, internals::{decode_u8, encode_u8}};
+use super::{common::{common_cmp_impls, common_cstr_impls, common_debug_impl, common_staticcstr_impls, common_owningcstr_impls}, internals::{check_no_nul, encode_into, encode_iter_latin1}, CStrCharType, StrError};
+#[cfg(feature = "alloc")]
+use super::{common::common_cstring_impls, internals::{decode_latin1, decode_u8, encode_latin1}};
+#[cfg(feature = "alloc")]
+use crate::prelude::*;
 
-common_cstr_impls!(U8CStr, u8, U8CString, DisplayU8CStr, U8CStrIter, StaticU8CStr);
-common_staticcstr_impls!(StaticU8CStr, u8, U8CString, U8CStr, DisplayU8CStr, StaticU8CStrIter, super::internals::encode_u8);
-common_cstring_impls!(U8CString, u8, U8CStr, DisplayU8CStr, U8CStringIter, super::internals::encode_u8);
+common_cstr_impls!(U8CStr, u8, U8CString, DisplayU8CStr, U8CStrIter, StaticU8CStr, super::internals::find_nul_u8, super::internals::find_nul_ptr_u8);
+common_staticcstr_impls!(StaticU8CStr, u8, U8CString, U8CStr, DisplayU8CStr, StaticU8CStrIter, super::internals::encode_iter_u8, super::internals::encode_const_u8, super::internals::find_nul_u8);
+#[cfg(feature = "alloc")]
+common_cstring_impls!(U8CString, u8, U8CStr, DisplayU8CStr, U8CStringIter, U8CStringMut, super::internals::encode_u8, super::internals::find_nul_u8);
 common_owningcstr_impls!(U8OwningCStr, u8, U8CString, U8CStr, DisplayU8CStr, U8OwningCStrIter);
 pub type CStr = U8CStr;
+#[cfg(feature = "alloc")]
 pub type CString = U8CString;
 
 impl U8CStr {
+  /// Interprets contents until nul-terminator as UTF-8 without copying
+  pub fn to_str(&self) -> Result<&str, core::str::Utf8Error> {
+    core::str::from_utf8(self.as_slice())
+  }
+}
+
+#[cfg(feature = "alloc")]
+impl U8CStr {
+  /// Decodes contents as UTF-8
+  /// NOTE: same as `decode_utf8`
   pub fn decode(&self) -> Option<String> {
     decode_u8(self.as_slice())
   }
+  /// Decodes contents as Latin-1, i.e. one char per byte
+  pub fn decode_latin1(&self) -> Option<String> {
+    decode_latin1(self.as_slice())
+  }
+  /// Decodes contents as UTF-8
+  /// NOTE: returns None if contents are not valid UTF-8
+  pub fn decode_utf8(&self) -> Option<String> {
+    decode_u8(self.as_slice())
+  }
+  /// Interprets contents until nul-terminator as UTF-8 replacing invalid sequences with U+FFFD
+  pub fn to_string_lossy(&self) -> alloc::borrow::Cow<'_, str> {
+    String::from_utf8_lossy(self.as_slice())
+  }
 }
 
 impl<const CAP: usize> StaticU8CStr<CAP> {
-  pub fn encode(data: &str) -> Option<Self> {
-    let encoded = encode_u8(data)?;
-    if encoded.len() > CAP {
-      return None;
+  /// Encodes `data` as UTF-8
+  /// NOTE: same as `encode_utf8`
+  pub fn encode(data: &str) -> Result<Self, StrError> {
+    <Self as CStrCharType>::encode(data)
+  }
+  /// Encodes `data` as Latin-1
+  /// NOTE: returns Err if `data` contains nul or chars above U+00FF or does not fit into `CAP`
+  pub fn encode_latin1(data: &str) -> Result<Self, StrError> {
+    let mut result = Self::zeroed();
+    let needed = encode_into(encode_iter_latin1(data), &mut result.0)?;
+    check_no_nul(&result.0[..core::cmp::min(needed, CAP)])?;
+    if needed > CAP {
+      return Err(StrError::CapacityExceeded { needed, capacity: CAP });
     }
-    Some(Self::from_slice(&encoded))
+    Ok(result)
   }
-  pub fn encode_truncate(data: &str) -> Option<Self> {
-    let encoded = encode_u8(data)?;
-    let len = core::cmp::min(encoded.len(), CAP);
-    Some(Self::from_slice(&encoded[..len]))
+  /// Encodes as much of `data` as fits into `CAP` bytes as UTF-8
+  /// NOTE: same as `encode_utf8_truncate`, never returns Err
+  pub fn encode_truncate(data: &str) -> Result<Self, StrError> {
+    Ok(Self::encode_utf8_truncate(data))
+  }
+  /// Encodes `data` as UTF-8
+  /// NOTE: returns Err if `data` contains nul or does not fit into `CAP` bytes
+  pub fn encode_utf8(data: &str) -> Result<Self, StrError> {
+    check_no_nul(data.as_bytes())?;
+    if data.len() > CAP {
+      return Err(StrError::CapacityExceeded {
+        needed: data.len(),
+        capacity: CAP,
+      });
+    }
+    Ok(Self::from_slice(data.as_bytes()))
+  }
+  /// Encodes as much of `data` as fits into `CAP` bytes as UTF-8
+  /// NOTE: multibyte sequence is never split at the truncation boundary
+  pub fn encode_utf8_truncate(data: &str) -> Self {
+    let mut len = core::cmp::min(data.len(), CAP);
+    while !data.is_char_boundary(len) {
+      len -= 1;
+    }
+    Self::from_slice(&data.as_bytes()[..len])
   }
 }
 
+#[cfg(feature = "alloc")]
 impl U8CString {
-  pub fn encode(data: &str) -> Option<Self> {
-    encode_u8(data).map(Into::into)
+  /// Encodes `data` as UTF-8
+  /// NOTE: same as `encode_utf8`
+  pub fn encode(data: &str) -> Result<Self, StrError> {
+    <Self as CStrCharType>::encode(data)
+  }
+  /// Encodes `data` as Latin-1
+  /// NOTE: returns Err if `data` contains nul or chars above U+00FF
+  pub fn encode_latin1(data: &str) -> Result<Self, StrError> {
+    Ok(Self::new(encode_latin1(data)?)?)
+  }
+  /// Encodes `data` as UTF-8
+  /// NOTE: returns Err if `data` contains nul
+  pub fn encode_utf8(data: &str) -> Result<Self, StrError> {
+    Ok(Self::new(data)?)
   }
 }
 
-#[cfg(not(feature = "no_std"))]
+#[cfg(feature = "std")]
 impl std::io::Write for &mut U8CStr {
   fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
     let writable = self.0.len() - 1;
     let mut mybuf = &mut self.0[0..writable];
     let written = mybuf.write(buf)?;
     self.0[written] = 0;
-    *self = unsafe { std::mem::transmute(&mut self.0[written..]) };
+    // SAFETY: the remainder still ends with nul-terminator, and it is reborrowed for the lifetime
+    // of the original reference since the written part is not accessible through `self` anymore
+    *self = unsafe { core::mem::transmute::<&mut [u8], &mut U8CStr>(&mut self.0[written..]) };
     Ok(written)
   }
 
@@ -50,7 +124,7 @@ impl std::io::Write for &mut U8CStr {
   }
 }
 
-#[cfg(not(feature = "no_std"))]
+#[cfg(feature = "std")]
 impl<const CAPACITY: usize> std::io::Write for StaticU8CStr<CAPACITY> {
   fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
     if buf.is_empty() {
@@ -67,21 +141,11 @@ impl<const CAPACITY: usize> std::io::Write for StaticU8CStr<CAPACITY> {
   }
 }
 
-#[cfg(not(feature = "no_std"))]
+#[cfg(feature = "std")]
 impl std::io::Write for U8CString {
   fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
-    let len = self.len_usize();
-    if buf.is_empty() {
-      return Ok(0);
-    }
-    self.0.truncate(len);
-    let len = self.0.write(buf);
-    if !matches!(self.0.last(), Some(&0)) {
-      self.0.push(0);
-    }
-    let cap = self.0.capacity();
-    self.0.resize(cap, 0);
-    len
+    self.push_slice(buf);
+    Ok(buf.len())
   }
 
   fn flush(&mut self) -> std::io::Result<()> {
@@ -91,12 +155,62 @@ impl std::io::Write for U8CString {
 
 impl<'a> From<&'a U8CStr> for &'a core::ffi::CStr {
   fn from(value: &'a U8CStr) -> Self {
-    unsafe { std::ffi::CStr::from_bytes_until_nul(value.as_slice_with_nul()).unwrap_unchecked() }
+    unsafe { core::ffi::CStr::from_bytes_until_nul(value.as_slice_with_nul()).unwrap_unchecked() }
   }
 }
 impl From<&core::ffi::CStr> for &U8CStr {
   fn from(value: &core::ffi::CStr) -> Self {
-    unsafe { std::mem::transmute(value.to_bytes_with_nul()) }
+    unsafe { core::mem::transmute(value.to_bytes_with_nul()) }
+  }
+}
+
+#[cfg(feature = "alloc")]
+impl From<U8CString> for alloc::ffi::CString {
+  /// NOTE: contents after the first nul-terminator are dropped,
+  /// the buffer is reused but shrunk to fit
+  fn from(value: U8CString) -> Self {
+    // SAFETY: `into_inner` truncates the vector at the first nul character
+    unsafe { alloc::ffi::CString::from_vec_with_nul_unchecked(value.into_inner()) }
+  }
+}
+
+#[cfg(feature = "alloc")]
+impl From<alloc::ffi::CString> for U8CString {
+  /// NOTE: reuses the buffer of `CString` without reallocation
+  fn from(value: alloc::ffi::CString) -> Self {
+    Self::from(value.into_bytes_with_nul())
+  }
+}
+
+#[cfg(feature = "alloc")]
+impl From<Box<U8CStr>> for Box<core::ffi::CStr> {
+  fn from(value: Box<U8CStr>) -> Self {
+    alloc::ffi::CString::from(U8CString::from(value)).into_boxed_c_str()
+  }
+}
+
+#[cfg(feature = "alloc")]
+impl From<Box<core::ffi::CStr>> for Box<U8CStr> {
+  fn from(value: Box<core::ffi::CStr>) -> Self {
+    U8CString::from(value.into_c_string()).into()
+  }
+}
+
+#[cfg(feature = "alloc")]
+impl U8CStr {
+  /// Converts `Cow` of `core::ffi::CStr` without copying
+  pub fn from_std_cow(value: alloc::borrow::Cow<'_, core::ffi::CStr>) -> alloc::borrow::Cow<'_, U8CStr> {
+    match value {
+      alloc::borrow::Cow::Borrowed(value) => alloc::borrow::Cow::Borrowed(value.into()),
+      alloc::borrow::Cow::Owned(value) => alloc::borrow::Cow::Owned(value.into()),
+    }
+  }
+  /// Converts `Cow` of `U8CStr` to `Cow` of `core::ffi::CStr` without copying borrowed data
+  pub fn into_std_cow(value: alloc::borrow::Cow<'_, U8CStr>) -> alloc::borrow::Cow<'_, core::ffi::CStr> {
+    match value {
+      alloc::borrow::Cow::Borrowed(value) => alloc::borrow::Cow::Borrowed(value.into()),
+      alloc::borrow::Cow::Owned(value) => alloc::borrow::Cow::Owned(value.into()),
+    }
   }
 }
 
@@ -300,6 +414,236 @@ mod tests {
     assert_eq!(tmp, abc);
   }
   #[test]
+  fn test_utf8_and_latin1() {
+    let string = U8CString::encode_utf8("\u{e9}\u{1F600}").unwrap();
+    assert_eq!(string.as_slice(), "\u{e9}\u{1F600}".as_bytes());
+    assert_eq!(string.to_str().unwrap(), "\u{e9}\u{1F600}");
+    assert_eq!(string.decode_utf8().unwrap(), "\u{e9}\u{1F600}");
+    assert_eq!(
+      U8CString::encode_latin1("a\u{1F600}"),
+      Err(StrError::InvalidCodePoint { index: 1 })
+    );
+    let string = U8CString::encode_latin1("\u{e9}").unwrap();
+    assert_eq!(string.as_slice(), b"\xE9");
+    assert_eq!(string.decode_latin1().unwrap(), "\u{e9}");
+    assert!(string.to_str().is_err());
+    assert_eq!(string.to_string_lossy(), "\u{FFFD}");
+  }
+  #[test]
+  fn test_static_utf8() {
+    assert_eq!(
+      StaticU8CStr::<2>::encode_utf8("\u{20AC}"),
+      Err(StrError::CapacityExceeded { needed: 3, capacity: 2 })
+    );
+    let string = StaticU8CStr::<4>::encode_utf8("\u{20AC}").unwrap();
+    assert_eq!(string.to_str().unwrap(), "\u{20AC}");
+    let string = StaticU8CStr::<4>::encode_utf8_truncate("a\u{20AC}\u{20AC}");
+    assert_eq!(string.to_str().unwrap(), "a\u{20AC}");
+  }
+  #[test]
+  fn test_default_encoding_is_utf8() {
+    let string = U8CString::encode("\u{e9}\u{20AC}").unwrap();
+    assert_eq!(string.as_slice(), "\u{e9}\u{20AC}".as_bytes());
+    assert_eq!(string.decode().unwrap(), "\u{e9}\u{20AC}");
+    let string = StaticU8CStr::<3>::encode("a\u{e9}").unwrap();
+    assert_eq!(string.as_slice(), b"a\xC3\xA9");
+    let string = StaticU8CStr::<3>::encode_truncate("a\u{e9}\u{20AC}").unwrap();
+    assert_eq!(string.as_slice(), b"a\xC3\xA9");
+    let string = StaticU8CStr::<2>::encode_latin1("a\u{e9}").unwrap();
+    assert_eq!(string.as_slice(), b"a\xE9");
+    assert_eq!(string.decode_latin1().unwrap(), "a\u{e9}");
+    assert!(string.decode().is_none());
+    assert_eq!(
+      StaticU8CStr::<2>::encode_latin1("a\u{20AC}"),
+      Err(StrError::InvalidCodePoint { index: 1 })
+    );
+  }
+  #[test]
+  fn test_std_cstring_conversions() {
+    let string = U8CString::from_slice(b"abc\0");
+    let ptr = string.as_ptr();
+    let std_string: std::ffi::CString = string.into();
+    assert_eq!(std_string.as_bytes_with_nul(), b"abc\0");
+    assert_eq!(std_string.as_ptr().cast(), ptr);
+    let ptr = std_string.as_ptr();
+    let string: U8CString = std_string.into();
+    assert_eq!(string.as_slice_with_nul(), b"abc\0");
+    assert_eq!(string.as_ptr(), ptr.cast());
+  }
+  #[test]
+  fn test_std_cstring_interior_nul() {
+    let string = U8CString::from_slice(b"abc\0def\0");
+    let std_string: std::ffi::CString = string.into();
+    assert_eq!(std_string.as_bytes_with_nul(), b"abc\0");
+    let boxed: Box<U8CStr> = U8CString::from_slice(b"abc\0def\0").into();
+    assert_eq!(boxed.as_slice_full(), b"abc\0");
+  }
+  #[test]
+  fn test_nul_written_through_guard_ends_string() {
+    let mut string = U8CString::from_slice(b"abc");
+    *string.as_mut_str().get_mut(1).unwrap() = 0;
+    assert_eq!(string.len_usize(), 1);
+    assert_eq!(string.as_ref().len_usize(), 1);
+    assert_eq!(string, U8CString::from_slice(b"a"));
+    let std_string: std::ffi::CString = string.clone().into();
+    assert_eq!(std_string.as_bytes_with_nul(), b"a\0");
+    string.push(b'd');
+    assert_eq!(string.as_slice_with_nul(), b"ad\0");
+    string.as_mut_str().as_mut_slice()[0] = 0;
+    assert_eq!(string.into_inner(), b"\0");
+  }
+  #[test]
+  fn test_smart_pointers() {
+    let cstr: &U8CStr = b"abc\0def\0".try_into().unwrap();
+    let boxed: Box<U8CStr> = cstr.into();
+    assert_eq!(boxed.as_slice_full(), b"abc\0");
+    let rc: std::rc::Rc<U8CStr> = cstr.into();
+    assert_eq!(rc.as_slice_full(), b"abc\0");
+    let arc: std::sync::Arc<U8CStr> = cstr.into();
+    assert_eq!(arc.as_slice_full(), b"abc\0");
+    let std_boxed: Box<core::ffi::CStr> = boxed.clone().into();
+    assert_eq!(std_boxed.to_bytes(), b"abc");
+    let string: U8CString = boxed.into();
+    assert_eq!(string.as_slice_with_nul(), b"abc\0");
+  }
+  #[test]
+  fn test_cow() {
+    let cstr: &U8CStr = b"abc\0".try_into().unwrap();
+    let mut cow: std::borrow::Cow<U8CStr> = cstr.into();
+    assert!(matches!(cow, std::borrow::Cow::Borrowed(_)));
+    *cow.to_mut().as_mut_str().get_mut(0).unwrap() = b'x';
+    assert_eq!(cow.as_slice(), b"xbc");
+    let std_cow = U8CStr::into_std_cow(cow);
+    assert_eq!(std_cow.to_bytes(), b"xbc");
+    let cow = U8CStr::from_std_cow(std_cow);
+    assert_eq!(cow.as_slice(), b"xbc");
+  }
+  #[test]
+  fn test_new_rejects_interior_nul() {
+    let string = U8CString::new(b"abc".to_vec()).unwrap();
+    assert_eq!(string.as_slice_with_nul(), b"abc\0");
+    let err = U8CString::new(b"ab\0c".to_vec()).unwrap_err();
+    assert_eq!(err.nul_position(), 2);
+    assert_eq!(err.into_vec(), b"ab\0c");
+    assert_eq!(
+      U8CString::try_from_slice(b"ab\0c\0"),
+      Err(StrError::InteriorNul { pos: 2 })
+    );
+    assert_eq!(U8CString::try_from_slice(b"abc\0").unwrap().as_slice(), b"abc");
+    assert_eq!(U8CString::encode("a\0"), Err(StrError::InteriorNul { pos: 1 }));
+  }
+  #[test]
+  fn test_capacity_errors() {
+    let cstr: &U8CStr = b"abcd\0".try_into().unwrap();
+    assert_eq!(
+      cstr.try_into_static::<3>(),
+      Err(StrError::CapacityExceeded { needed: 4, capacity: 3 })
+    );
+    assert_eq!(cstr.try_into_static::<4>().unwrap().as_slice(), b"abcd");
+    assert_eq!(
+      StaticU8CStr::<3>::try_from_slice(b"abcd\0"),
+      Err(StrError::CapacityExceeded { needed: 4, capacity: 3 })
+    );
+    assert_eq!(
+      StaticU8CStr::<8>::try_from_slice(b"abcd"),
+      Err(StrError::NulNotFound)
+    );
+    assert_eq!(
+      unsafe { StaticU8CStr::<3>::from_ptr(c"abcdef".as_ptr().cast()) },
+      Err(StrError::CapacityExceeded { needed: 4, capacity: 3 })
+    );
+  }
+  #[test]
+  fn test_cross_type_comparison() {
+    let cstr: &U8CStr = b"abc\0".try_into().unwrap();
+    let string = U8CString::from_slice(b"abc");
+    let static_str = StaticU8CStr::<8>::try_from_slice(b"abc\0").unwrap();
+    assert_eq!(cstr, string);
+    assert_eq!(string, cstr);
+    assert_eq!(static_str, string);
+    assert_eq!(string, static_str);
+    assert_eq!(static_str, cstr);
+    assert_eq!(cstr, static_str);
+    assert_eq!(cstr, "abc");
+    assert_eq!("abc", string);
+    assert_eq!(static_str, "abc");
+    assert_ne!(string, "ab");
+    assert_eq!(*cstr, b"abc"[..]);
+    assert_eq!(b"abc"[..], string);
+    assert!(U8CString::from_slice(b"ab") < string);
+    assert!(cstr < U8CStr::try_from_slice(b"abd\0").unwrap());
+  }
+  #[test]
+  fn test_map_keys() {
+    let mut hash_map = std::collections::HashMap::new();
+    hash_map.insert(U8CString::from_slice(b"abc\0def\0"), 1);
+    let key: &U8CStr = b"abc\0".try_into().unwrap();
+    assert_eq!(hash_map.get(key), Some(&1));
+    let mut btree_map = std::collections::BTreeMap::new();
+    btree_map.insert(U8CString::from_slice(b"b"), 2);
+    btree_map.insert(U8CString::from_slice(b"a"), 1);
+    let keys: Vec<_> = btree_map.keys().map(|k| k.as_slice().to_vec()).collect();
+    assert_eq!(keys, [b"a".to_vec(), b"b".to_vec()]);
+    assert_eq!(btree_map.get(U8CStr::try_from_slice(b"b\0").unwrap()), Some(&2));
+  }
+  #[test]
+  fn test_debug() {
+    let cstr: &U8CStr = b"a'\t\xC3\xA9\xFF\0".try_into().unwrap();
+    assert_eq!(format!("{:?}", cstr), "c\"a'\\t\u{e9}\\xff\"");
+    #[derive(Debug)]
+    #[allow(dead_code)]
+    struct Holder {
+      name: U8CString,
+    }
+    let holder = Holder { name: U8CString::from_slice(b"abc") };
+    assert_eq!(format!("{:?}", holder), "Holder { name: c\"abc\" }");
+  }
+  #[test]
+  fn test_string_manipulation() {
+    let mut string = U8CString::from_slice(b"hello");
+    string.push_slice(b" world");
+    string.insert_slice(0, b">> ");
+    assert_eq!(string, ">> hello world");
+    assert_eq!(string.push_char('\u{263A}'), Ok(()));
+    assert_eq!(string.as_slice(), ">> hello world\u{263A}".as_bytes());
+    string.retain(|ch| ch != b'l' && ch.is_ascii());
+    assert_eq!(string, ">> heo word");
+    assert_eq!(string.replace(b"o", b"00"), ">> he00 w00rd");
+    assert_eq!(string.range(2..).trim_start(), "heo word");
+    assert!(string.starts_with(b">> heo"));
+    assert_eq!(string.find(b"word"), Some(7));
+    assert_eq!(string.find(b"xyz"), None);
+  }
+  #[test]
+  fn test_static_try_push() {
+    let mut static_str = StaticU8CStr::<4>::from_slice(b"ab");
+    assert_eq!(static_str.try_push_slice(b"cd"), Ok(()));
+    assert_eq!(static_str.try_push(b'e'), Err(StrError::CapacityExceeded { needed: 5, capacity: 4 }));
+    assert_eq!(static_str, "abcd");
+    static_str.clear();
+    assert_eq!(static_str, "");
+    assert_eq!(static_str.try_insert_slice(0, b"xyz"), Ok(()));
+    assert_eq!(static_str.as_slice_with_nul(), b"xyz\0");
+  }
+  #[test]
+  fn test_str_comparison_encodings() {
+    let string = U8CString::encode_utf8("\u{e9}").unwrap();
+    assert_eq!(string, "\u{e9}");
+    assert_eq!(format!("{:?}", string), "c\"\u{e9}\"");
+    let string = U8CString::encode_latin1("\u{e9}").unwrap();
+    assert_ne!(string, "\u{e9}");
+    assert_eq!(format!("{:?}", string), "c\"\\xe9\"");
+  }
+  #[test]
+  fn test_utf8_case_conversions() {
+    let mut string = U8CString::encode("\u{FF}ber \u{DF}").unwrap();
+    string.push_slice(b"\xFF");
+    let mut upper = U8CString::encode("\u{178}BER SS").unwrap();
+    upper.push_slice(b"\xFF");
+    assert_eq!(string.to_uppercase(), upper);
+    assert_eq!(string.to_ascii_uppercase().as_slice(), b"\xC3\xBFBER \xC3\x9F\xFF");
+  }
+  #[test]
   fn test_owning_str() {
     extern "C" {
       fn free(ptr: *mut std::ffi::c_void);
@@ -312,22 +656,90 @@ mod tests {
       cnt_c.store(true, core::sync::atomic::Ordering::Relaxed);
     };
     let data = unsafe { calloc(1, 5) }.cast();
-    let tmp = unsafe { std::slice::from_raw_parts_mut(data, 5) };
+    let tmp = unsafe { core::slice::from_raw_parts_mut(data, 5) };
     tmp.copy_from_slice(b"abcd\0");
-    let string = unsafe { U8OwningCStr::from_ptr_safe_deleter(data, deleter) };
+    let string = unsafe { U8OwningCStr::from_ptr_safe_deleter(data, crate::strings::FnDeleter(deleter)) };
     assert_eq!(format!("{}", string.display()), "abcd");
     drop(string);
     assert!(cnt.load(core::sync::atomic::Ordering::Relaxed))
   }
   #[test]
+  #[cfg(feature = "libc")]
   fn test_owning_str_free() {
     extern "C" {
       fn calloc(num: usize, size: usize) -> *mut std::ffi::c_void;
     }
     let data = unsafe { calloc(1, 5) }.cast();
-    let tmp = unsafe { std::slice::from_raw_parts_mut(data, 5) };
+    let tmp = unsafe { core::slice::from_raw_parts_mut(data, 5) };
     tmp.copy_from_slice(b"abcd\0");
     let string = unsafe { U8OwningCStr::new(data) };
     assert_eq!(format!("{}", string.display()), "abcd");
   }
+  #[test]
+  #[cfg(feature = "libc")]
+  fn test_owning_str_alloc_copy() {
+    extern "C" {
+      fn free(ptr: *mut std::ffi::c_void);
+    }
+    let source = U8CString::from_slice(b"abcd");
+    let string = U8OwningCStr::alloc_copy(&source);
+    assert_eq!(*string, *source);
+    assert_eq!(string.as_slice_with_nul(), b"abcd\0");
+    let raw = string.into_raw();
+    assert_eq!(unsafe { U8CStr::from_ptr(raw) }, "abcd");
+    unsafe { free(raw.cast()) };
+    let parsed: U8OwningCStr<_> = "\u{FF}x".parse().unwrap();
+    assert_eq!(parsed.as_slice(), "\u{FF}x".as_bytes());
+    assert!("a\0".parse::<U8OwningCStr<_>>().is_err());
+  }
+  #[test]
+  #[cfg(feature = "libc")]
+  fn test_owning_str_custom_allocator() {
+    use crate::strings::{CAllocator, LibcAlloc};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    static FREED: AtomicUsize = AtomicUsize::new(0);
+    struct Counting;
+    unsafe impl CAllocator for Counting {
+      fn allocate(&self, size: usize, align: usize) -> *mut u8 {
+        LibcAlloc.allocate(size, align)
+      }
+      unsafe fn free(&self, ptr: *mut u8) {
+        FREED.fetch_add(1, Ordering::Relaxed);
+        LibcAlloc.free(ptr)
+      }
+    }
+    let string = U8OwningCStr::alloc_copy_in(&U8CString::from_slice(b"xyz"), Counting);
+    assert_eq!(string, "xyz");
+    drop(string);
+    assert_eq!(FREED.load(Ordering::Relaxed), 1);
+  }
+  #[test]
+  #[cfg(feature = "libc")]
+  fn test_owning_str_deleters() {
+    use crate::strings::{ExternCDeleter, NoopDeleter};
+    extern "C" {
+      fn free(ptr: *mut std::ffi::c_void);
+    }
+    let mut data = *b"abc\0";
+    let borrowed = unsafe { U8OwningCStr::from_ptr_safe_deleter(data.as_mut_ptr(), NoopDeleter) };
+    assert_eq!(borrowed, "abc");
+    drop(borrowed);
+    let raw = U8OwningCStr::alloc_copy(&U8CString::from_slice(b"xyz")).into_raw();
+    let string: U8OwningCStr<ExternCDeleter<std::ffi::c_void>> = unsafe { U8OwningCStr::from_ptr(raw, free) };
+    let handle = std::thread::spawn(move || string.len_usize());
+    assert_eq!(handle.join().unwrap(), 3);
+  }
+
+  #[test]
+  fn fmt_writes_utf8() {
+    // NOTE: `std::io::Write` is also in scope, so `write!` would be ambiguous
+    use core::fmt::Write as FmtWrite;
+    let mut string = U8CString::default();
+    FmtWrite::write_fmt(&mut string, format_args!("\u{e9}{}", 1)).unwrap();
+    assert_eq!(string.as_slice_with_nul(), b"\xC3\xA91\0");
+    let mut static_str = StaticU8CStr::<3>::zeroed();
+    assert!(static_str.write_str("\u{20AC}\u{e9}").is_err());
+    static_str.write_str("\u{20AC}").unwrap();
+    assert_eq!(static_str.as_slice(), "\u{20AC}".as_bytes());
+  }
 }
@@ -0,0 +1,180 @@
+use super::{CStrCharType, StrError, U16CStr, U16CString, U32CStr, U32CString, U8CStr, U8CString};
+use crate::prelude::*;
+
+/// Owned array of C strings together with a null-terminated array of pointers to them,
+/// i.e. `argv`/`envp`-style argument of C functions
+/// NOTE: pointers stay valid until the array is mutated or dropped
+pub struct CStrArray<T> {
+  strings: Vec<Box<[T]>>,
+  pointers: Vec<*const T>,
+}
+
+unsafe impl<T: Send> Send for CStrArray<T> {}
+unsafe impl<T: Sync> Sync for CStrArray<T> {}
+
+impl<T> Default for CStrArray<T> {
+  fn default() -> Self {
+    Self {
+      strings: Vec::new(),
+      pointers: vec![core::ptr::null()],
+    }
+  }
+}
+
+impl<T> CStrArray<T> {
+  /// Constructs an empty array
+  pub fn new() -> Self {
+    Self::default()
+  }
+  /// Appends a string that already ends with a nul-terminator
+  fn push_with_nul(&mut self, data: Box<[T]>) {
+    self.pointers.pop();
+    self.pointers.push(data.as_ptr());
+    self.pointers.push(core::ptr::null());
+    self.strings.push(data);
+  }
+  /// Returns a pointer to the null-terminated array of pointers to strings
+  pub fn as_ptr(&self) -> *const *const T {
+    self.pointers.as_ptr()
+  }
+  /// Returns the array of pointers to strings including the terminating null pointer
+  pub fn as_ptr_slice(&self) -> &[*const T] {
+    &self.pointers
+  }
+  /// Returns the number of strings in the array
+  pub fn len(&self) -> usize {
+    self.strings.len()
+  }
+  /// Checks whether the array does not contain any strings
+  pub fn is_empty(&self) -> bool {
+    self.strings.is_empty()
+  }
+}
+
+impl<T: Clone> Clone for CStrArray<T> {
+  fn clone(&self) -> Self {
+    let mut result = Self::default();
+    for data in &self.strings {
+      result.push_with_nul(data.clone());
+    }
+    result
+  }
+}
+
+macro_rules! cstr_array_impls {
+  ($type:ty, $cstr:ident, $cstring:ident) => {
+    impl CStrArray<$type> {
+      /// Encodes every string of `items` and constructs an array of them
+      /// using the default encoding of the code unit (UTF-8 for u8, UTF-16 for u16 and UTF-32 for u32)
+      /// NOTE: this function returns Err in case of an item contains nul
+      /// or is unrepresentable in the target encoding
+      pub fn encode<S: AsRef<str>>(items: impl IntoIterator<Item = S>) -> Result<Self, StrError> {
+        let mut result = Self::default();
+        for item in items {
+          result.push(&<$cstring as CStrCharType>::encode(item.as_ref())?);
+        }
+        Ok(result)
+      }
+      /// Constructs an array by copying `items`
+      pub fn from_cstrs<S: AsRef<$cstr>>(items: impl IntoIterator<Item = S>) -> Self {
+        let mut result = Self::default();
+        for item in items {
+          result.push(item.as_ref());
+        }
+        result
+      }
+      /// Appends a copy of `item` to the end of the array
+      pub fn push(&mut self, item: &$cstr) {
+        self.push_with_nul(item.as_slice_with_nul().into());
+      }
+      /// Returns the string at position `index`
+      pub fn get(&self, index: usize) -> Option<&$cstr> {
+        let data = self.strings.get(index)?;
+        Some(unsafe { $cstr::from_slice_unchecked(data) })
+      }
+      /// Returns an iterator over strings of the array
+      pub fn iter(&self) -> impl Iterator<Item = &$cstr> {
+        self.strings.iter().map(|data| unsafe { $cstr::from_slice_unchecked(data) })
+      }
+      /// Collects strings of a foreign null-terminated array of pointers
+      /// NOTE: lifetime of the returned values is inferred from context
+      /// # Safety
+      /// `data` should point to a valid null-terminated array of pointers
+      /// to valid nul-terminated strings
+      pub unsafe fn from_ptr<'a>(data: *const *const $type) -> Vec<&'a $cstr> {
+        Self::from_ptr_n(data, usize::MAX)
+      }
+      /// Collects at most `n` strings of a foreign array of pointers, stops early at null pointer,
+      /// e.g. `argv` given `argc`
+      /// NOTE: lifetime of the returned values is inferred from context
+      /// # Safety
+      /// `data` should point to a valid array of at least `n` pointers (or null-terminated one)
+      /// to valid nul-terminated strings
+      pub unsafe fn from_ptr_n<'a>(data: *const *const $type, n: usize) -> Vec<&'a $cstr> {
+        let mut result = Vec::new();
+        while result.len() < n {
+          let ptr = *data.add(result.len());
+          if ptr.is_null() {
+            break;
+          }
+          result.push($cstr::from_ptr(ptr));
+        }
+        result
+      }
+    }
+
+    impl core::fmt::Debug for CStrArray<$type> {
+      fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+      }
+    }
+  };
+}
+
+cstr_array_impls!(u8, U8CStr, U8CString);
+cstr_array_impls!(u16, U16CStr, U16CString);
+cstr_array_impls!(u32, U32CStr, U32CString);
+
+#[cfg(test)]
+mod tests {
+  use super::CStrArray;
+  use crate::strings::{StrError, U8CStr, U16CString};
+
+  #[test]
+  fn builds_argv() {
+    let argv = CStrArray::<u8>::encode(["prog", "--flag", "value"]).unwrap();
+    assert_eq!(argv.len(), 3);
+    let pointers = argv.as_ptr_slice();
+    assert_eq!(pointers.len(), 4);
+    assert!(pointers[3].is_null());
+    assert_eq!(unsafe { U8CStr::from_ptr(pointers[1]) }, "--flag");
+    assert_eq!(CStrArray::<u8>::encode(["a\0b"]).unwrap_err(), StrError::InteriorNul { pos: 1 });
+  }
+  #[test]
+  fn encodes_non_ascii_as_utf8() {
+    let argv = CStrArray::<u8>::encode(["caf\u{e9}", "\u{1F600}"]).unwrap();
+    let first = unsafe { U8CStr::from_ptr(argv.as_ptr_slice()[0]) };
+    assert_eq!(first.as_slice(), "caf\u{e9}".as_bytes());
+    assert_eq!(argv.get(1).unwrap().to_str().unwrap(), "\u{1F600}");
+  }
+  #[test]
+  fn parses_foreign_pointer() {
+    let argv = CStrArray::<u8>::encode(["prog", "", "x"]).unwrap();
+    let parsed = unsafe { CStrArray::<u8>::from_ptr(argv.as_ptr()) };
+    assert_eq!(parsed, ["prog", "", "x"]);
+    let limited = unsafe { CStrArray::<u8>::from_ptr_n(argv.as_ptr(), 2) };
+    assert_eq!(limited, ["prog", ""]);
+    let empty = CStrArray::<u8>::new();
+    assert!(unsafe { CStrArray::<u8>::from_ptr(empty.as_ptr()) }.is_empty());
+  }
+  #[test]
+  fn clones_wide() {
+    let mut args = CStrArray::<u16>::encode(["a"]).unwrap();
+    args.push(&U16CString::encode("\u{1F600}").unwrap());
+    let cloned = args.clone();
+    drop(args);
+    let parsed = unsafe { CStrArray::<u16>::from_ptr(cloned.as_ptr()) };
+    assert_eq!(parsed, ["a", "\u{1F600}"]);
+    assert_eq!(format!("{:?}", cloned), "[u16c\"a\", u16c\"\u{1F600}\"]");
+  }
+}
@@ -0,0 +1,227 @@
+use super::internals::{CaseUnit, DebugUnits};
+use super::{
+  CStrCharType, StrError, U16CStr, U16CString, U16MultiCStr, U32CStr, U32CString, U32MultiCStr, U8CStr,
+  U8CString, U8MultiCStr,
+};
+use crate::prelude::*;
+
+/// Environment block, i.e. a list of `KEY=VALUE` strings terminated by an empty string
+/// (`KEY=VALUE\0KEY2=VALUE2\0\0`) as expected by `CreateProcess` or captured from C
+/// NOTE: blocks of wide characters (`u16`, `u32`) are sorted and queried by key ignoring case,
+/// blocks of `u8` are sorted and queried by exact key
+pub struct EnvBlock<T>(Vec<T>);
+
+macro_rules! env_block_impls {
+  ($type:ty, $multi:ident, $cstr:ident, $cstring:ident, $ignore_case:expr) => {
+    impl Default for EnvBlock<$type> {
+      fn default() -> Self {
+        Self(vec![0, 0])
+      }
+    }
+
+    impl EnvBlock<$type> {
+      /// Constructs an empty block
+      pub fn new() -> Self {
+        Self::default()
+      }
+      fn keys_cmp(lhs: &[$type], rhs: &[$type]) -> core::cmp::Ordering {
+        if $ignore_case {
+          <$type>::fold_case(lhs).cmp(<$type>::fold_case(rhs))
+        } else {
+          lhs.cmp(rhs)
+        }
+      }
+      /// Splits `KEY=VALUE` entry into key and value
+      /// NOTE: leading `=` is considered a part of the key (e.g. `=C:=C:\dir` on Windows)
+      fn split_entry(entry: &$cstr) -> (&[$type], &$cstr) {
+        let data = entry.as_slice();
+        match data.iter().skip(1).position(|c| *c == b'=' as $type) {
+          Some(pos) => (&data[..pos + 1], entry.range(pos + 2..)),
+          None => (data, entry.range(data.len()..)),
+        }
+      }
+      /// Sorts entries by key and constructs a block of them
+      /// NOTE: in case of duplicate keys the last entry is kept
+      fn from_entries(mut entries: Vec<$cstring>) -> Self {
+        entries.sort_by(|lhs, rhs| Self::keys_cmp(Self::split_entry(lhs).0, Self::split_entry(rhs).0));
+        let mut unique: Vec<$cstring> = Vec::with_capacity(entries.len());
+        for entry in entries {
+          match unique.last_mut() {
+            Some(last) if Self::keys_cmp(Self::split_entry(last).0, Self::split_entry(&entry).0).is_eq() => {
+              *last = entry
+            }
+            _ => unique.push(entry),
+          }
+        }
+        let mut data = Vec::new();
+        for entry in &unique {
+          data.extend_from_slice(entry.as_slice_with_nul());
+        }
+        data.push(0);
+        if unique.is_empty() {
+          data.push(0);
+        }
+        Self(data)
+      }
+      /// Encodes `KEY=VALUE` entries of `vars` and constructs a sorted block of them,
+      /// e.g. from `BTreeMap<String, String>`
+      /// NOTE: the default encoding of the code unit is used (UTF-8 for u8, UTF-16 for u16 and UTF-32 for u32),
+      /// same as OS strings are stored by `from_os_vars`
+      /// NOTE: this function returns Err in case of a key or value contains nul
+      /// or is unrepresentable in the target encoding
+      pub fn from_vars<K: AsRef<str>, V: AsRef<str>>(
+        vars: impl IntoIterator<Item = (K, V)>,
+      ) -> Result<Self, StrError> {
+        let entries = vars
+          .into_iter()
+          .map(|(key, value)| <$cstring as CStrCharType>::encode(&format!("{}={}", key.as_ref(), value.as_ref())))
+          .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self::from_entries(entries))
+      }
+      /// Copies a block from the beginning of the slice
+      /// NOTE: this function returns Err in case of `data` does not contain the terminating empty string
+      pub fn try_from_slice(data: &[$type]) -> Result<Self, StrError> {
+        Ok(Self($multi::try_from_slice(data)?.to_owned().into_vec()))
+      }
+      /// Copies a block given a pointer to it
+      /// # Safety
+      /// `data` should point to a valid memory where the block is stored
+      pub unsafe fn from_ptr(data: *const $type) -> Self {
+        Self($multi::from_ptr(data).to_owned().into_vec())
+      }
+      /// Returns the block as a multi-string list of `KEY=VALUE` entries
+      pub fn as_multi(&self) -> &$multi {
+        unsafe { $multi::from_slice_unchecked(&self.0) }
+      }
+      /// Returns the raw double nul-terminated buffer
+      pub fn as_slice_with_nul(&self) -> &[$type] {
+        &self.0
+      }
+      /// Returns a const pointer to the underlying data
+      pub fn as_ptr(&self) -> *const $type {
+        self.0.as_ptr()
+      }
+      /// Checks whether the block does not contain any entries
+      pub fn is_empty(&self) -> bool {
+        self.as_multi().is_empty()
+      }
+      /// Returns an iterator over keys and values of the block
+      pub fn iter(&self) -> impl Iterator<Item = (&[$type], &$cstr)> {
+        self.as_multi().iter().map(Self::split_entry)
+      }
+      /// Returns the value of variable `key`
+      /// NOTE: `key` is encoded the same way as by `from_vars`
+      pub fn get(&self, key: &str) -> Option<&$cstr> {
+        let key = <$cstring as CStrCharType>::encode(key).ok()?;
+        self
+          .iter()
+          .find(|(name, _)| Self::keys_cmp(name, key.as_slice()).is_eq())
+          .map(|(_, value)| value)
+      }
+    }
+
+    impl core::fmt::Debug for EnvBlock<$type> {
+      fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_map().entries(self.iter().map(|(key, value)| (DebugUnits(key), value))).finish()
+      }
+    }
+  };
+}
+
+env_block_impls!(u8, U8MultiCStr, U8CStr, U8CString, false);
+env_block_impls!(u16, U16MultiCStr, U16CStr, U16CString, true);
+env_block_impls!(u32, U32MultiCStr, U32CStr, U32CString, true);
+
+#[cfg(feature = "std")]
+macro_rules! env_block_os_impls {
+  ($type:ty, $cstring:ident) => {
+    impl EnvBlock<$type> {
+      /// Constructs a sorted block from variables of the current process
+      pub fn from_env() -> Self {
+        Self::from_os_vars(std::env::vars_os())
+      }
+      /// Constructs a sorted block from `KEY=VALUE` entries of `vars`
+      pub fn from_os_vars<K: AsRef<std::ffi::OsStr>, V: AsRef<std::ffi::OsStr>>(
+        vars: impl IntoIterator<Item = (K, V)>,
+      ) -> Self {
+        let entries = vars
+          .into_iter()
+          .map(|(key, value)| {
+            let mut entry = std::ffi::OsString::from(key.as_ref());
+            entry.push("=");
+            entry.push(value.as_ref());
+            $cstring::from(entry)
+          })
+          .collect();
+        Self::from_entries(entries)
+      }
+    }
+  };
+}
+
+#[cfg(all(unix, feature = "std"))]
+env_block_os_impls!(u8, U8CString);
+#[cfg(all(unix, feature = "std"))]
+env_block_os_impls!(u32, U32CString);
+#[cfg(all(windows, feature = "std"))]
+env_block_os_impls!(u16, U16CString);
+
+#[cfg(test)]
+mod tests {
+  use std::collections::BTreeMap;
+
+  use super::EnvBlock;
+  use crate::strings::StrError;
+
+  #[test]
+  fn builds_from_map() {
+    let map = BTreeMap::from([("PATH", "/bin"), ("HOME", "/root"), ("EMPTY", "")]);
+    let block = EnvBlock::<u8>::from_vars(&map).unwrap();
+    assert_eq!(block.as_slice_with_nul(), b"EMPTY=\0HOME=/root\0PATH=/bin\0\0");
+    assert_eq!(block.get("HOME").unwrap(), "/root");
+    assert_eq!(block.get("EMPTY").unwrap(), "");
+    assert!(block.get("home").is_none());
+    assert_eq!(EnvBlock::<u8>::from_vars([("A", "\0")]).unwrap_err(), StrError::InteriorNul { pos: 2 });
+    assert_eq!(EnvBlock::<u8>::new().as_slice_with_nul(), b"\0\0");
+  }
+  #[test]
+  fn sorts_wide_ignoring_case() {
+    let block = EnvBlock::<u32>::from_vars([("b", "1"), ("A", "2"), ("a", "3"), ("=C:", "C:\\")]).unwrap();
+    let keys: Vec<String> = block.iter().map(|(key, _)| key.iter().map(|c| char::from_u32(*c).unwrap()).collect()).collect();
+    assert_eq!(keys, ["=C:", "a", "b"]);
+    assert_eq!(block.get("A").unwrap(), "3");
+    assert_eq!(block.get("=c:").unwrap(), "C:\\");
+  }
+  #[test]
+  fn parses_pointer() {
+    let data = b"X=1\0Y=a=b\0\0ignored";
+    let block = unsafe { EnvBlock::<u8>::from_ptr(data.as_ptr()) };
+    assert_eq!(block.get("Y").unwrap(), "a=b");
+    assert_eq!(format!("{:?}", block), "{c\"X\": c\"1\", c\"Y\": c\"a=b\"}");
+    assert_eq!(EnvBlock::<u8>::try_from_slice(b"X=1\0").unwrap_err(), StrError::NulNotFound);
+  }
+  #[cfg(unix)]
+  #[test]
+  fn builds_from_os_vars() {
+    use std::os::unix::ffi::OsStrExt;
+    let vars = [
+      (std::ffi::OsStr::new("CUTILS_ENV_BLOCK_TEST"), std::ffi::OsStr::new("value")),
+      (std::ffi::OsStr::new("RAW"), std::ffi::OsStr::from_bytes(b"\xFF")),
+    ];
+    let narrow = EnvBlock::<u8>::from_os_vars(vars);
+    assert_eq!(narrow.get("CUTILS_ENV_BLOCK_TEST").unwrap(), "value");
+    assert_eq!(narrow.as_slice_with_nul(), b"CUTILS_ENV_BLOCK_TEST=value\0RAW=\xFF\0\0");
+    let wide = EnvBlock::<u32>::from_os_vars(vars);
+    assert_eq!(wide.get("cutils_env_block_test").unwrap(), "value");
+  }
+  #[cfg(unix)]
+  #[test]
+  fn round_trips_non_ascii_os_vars() {
+    let vars = [("CAF\u{c9}", "cr\u{e8}me \u{1F600}")];
+    let narrow = EnvBlock::<u8>::from_os_vars(vars);
+    assert_eq!(narrow.get("CAF\u{c9}").unwrap().to_str().unwrap(), "cr\u{e8}me \u{1F600}");
+    assert_eq!(narrow.as_slice_with_nul(), EnvBlock::<u8>::from_vars(vars).unwrap().as_slice_with_nul());
+    let wide = EnvBlock::<u32>::from_os_vars(vars);
+    assert_eq!(wide.get("caf\u{e9}").unwrap(), "cr\u{e8}me \u{1F600}");
+  }
+}
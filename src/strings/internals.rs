@@ -1,5 +1,8 @@
 pub use core;
 
+#[cfg(feature = "alloc")]
+use crate::prelude::*;
+
 pub const fn panic_on_invalid_utf8(slice: &[u8]) {
   if core::str::from_utf8(slice).is_err() {
     panic!("Invalid utf-8");
@@ -13,44 +16,546 @@ pub const fn check_is_valid_utf8(slice: &[u8]) -> Result<(), core::str::Utf8Erro
   }
 }
 
-pub fn encode_u8(utf8: &str) -> Option<Vec<u8>> {
-  utf8.chars().map(| c| {
+// Default encoding of u8 strings is UTF-8, so every string is representable
+pub fn encode_iter_u8(utf8: &str) -> impl Iterator<Item = Result<u8, super::StrError>> + '_ {
+  utf8.bytes().map(Ok)
+}
+
+pub fn encode_iter_latin1(utf8: &str) -> impl Iterator<Item = Result<u8, super::StrError>> + '_ {
+  utf8.char_indices().map(|(index, c)| {
     if c as u32 > (u8::MAX as u32) {
-      None
+      Err(super::StrError::InvalidCodePoint { index })
     } else {
-      Some(c as u8)
+      Ok(c as u8)
     }
-  }).collect()
+  })
 }
 
-pub fn encode_u16(utf8: &str) -> Option<Vec<u16>> {
-  utf8.chars().map(|c| {
-    if c as u32 > (u16::MAX as u32) {
-      None
-    } else {
-      Some(c as u16)
+pub fn encode_iter_u16(utf8: &str) -> impl Iterator<Item = Result<u16, super::StrError>> + '_ {
+  utf8.encode_utf16().map(Ok)
+}
+
+pub fn encode_iter_u32(utf8: &str) -> impl Iterator<Item = Result<u32, super::StrError>> + '_ {
+  utf8.chars().map(|c| Ok(c as u32))
+}
+
+// Writes as many encoded units as fit into `buf` and returns the total number of units in the source
+// NOTE: stops at the first unrepresentable char even if it is past the end of `buf`
+pub fn encode_into<T: Copy>(
+  encoded: impl Iterator<Item = Result<T, super::StrError>>,
+  buf: &mut [T],
+) -> Result<usize, super::StrError> {
+  let mut needed = 0;
+  for unit in encoded {
+    let unit = unit?;
+    if let Some(slot) = buf.get_mut(needed) {
+      *slot = unit;
     }
-  }).collect()
+    needed += 1;
+  }
+  Ok(needed)
 }
 
-pub fn encode_u32(utf8: &str) -> Option<Vec<u32>> {
-  Some(utf8.chars().map(|c| {
-    c as u32
-  }).collect())
+#[cfg(feature = "alloc")]
+pub fn encode_u8(utf8: &str) -> Result<Vec<u8>, super::StrError> {
+  Ok(utf8.as_bytes().to_vec())
 }
 
+#[cfg(feature = "alloc")]
+pub fn encode_latin1(utf8: &str) -> Result<Vec<u8>, super::StrError> {
+  encode_iter_latin1(utf8).collect()
+}
+
+#[cfg(feature = "alloc")]
+pub fn encode_u16(utf8: &str) -> Result<Vec<u16>, super::StrError> {
+  encode_iter_u16(utf8).collect()
+}
+
+#[cfg(feature = "alloc")]
+pub fn encode_u32(utf8: &str) -> Result<Vec<u32>, super::StrError> {
+  encode_iter_u32(utf8).collect()
+}
+
+pub fn check_no_nul<T: Default + PartialEq>(data: &[T]) -> Result<(), super::StrError> {
+  let nul = T::default();
+  match data.iter().position(|c| *c == nul) {
+    Some(pos) => Err(super::StrError::InteriorNul { pos }),
+    None => Ok(()),
+  }
+}
+
+#[cfg(feature = "alloc")]
 pub fn decode_u8(data: &[u8]) -> Option<String> {
+  core::str::from_utf8(data).ok().map(ToOwned::to_owned)
+}
+
+#[cfg(feature = "alloc")]
+pub fn decode_latin1(data: &[u8]) -> Option<String> {
   data.iter().copied().map(Into::into).map(char::from_u32).collect()
 }
 
+#[cfg(feature = "alloc")]
 pub fn decode_u16(data: &[u16]) -> Option<String> {
-  data.iter().copied().map(Into::into).map(char::from_u32).collect()
+  char::decode_utf16(data.iter().copied()).map(Result::ok).collect()
+}
+
+#[cfg(feature = "alloc")]
+pub fn decode_u16_lossy(data: &[u16]) -> String {
+  char::decode_utf16(data.iter().copied())
+    .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+    .collect()
+}
+
+pub const fn is_high_surrogate(unit: u16) -> bool {
+  matches!(unit, 0xD800..=0xDBFF)
 }
 
+#[cfg(feature = "alloc")]
 pub fn decode_u32(data: &[u32]) -> Option<String> {
   data.iter().copied().map(char::from_u32).collect()
 }
 
+#[cfg(feature = "alloc")]
+// Bytes that are not valid UTF-8 are mapped to lone surrogates `0xDC80..=0xDCFF`
+// (same trick as python's `surrogateescape`), so that the conversion can be reversed
+pub fn encode_u32_lossless(mut bytes: &[u8]) -> Vec<u32> {
+  let mut result = Vec::with_capacity(bytes.len());
+  loop {
+    match core::str::from_utf8(bytes) {
+      Ok(valid) => {
+        result.extend(valid.chars().map(|c| c as u32));
+        return result;
+      }
+      Err(err) => {
+        let (valid, invalid) = bytes.split_at(err.valid_up_to());
+        let valid = unsafe { core::str::from_utf8_unchecked(valid) };
+        result.extend(valid.chars().map(|c| c as u32));
+        let invalid_len = err.error_len().unwrap_or(invalid.len());
+        result.extend(invalid[..invalid_len].iter().map(|b| 0xDC00 | *b as u32));
+        bytes = &invalid[invalid_len..];
+      }
+    }
+  }
+}
+
+#[cfg(feature = "alloc")]
+// Reverse of `encode_u32_lossless`, code points that are not valid chars are replaced with U+FFFD
+pub fn decode_u32_lossless(data: &[u32]) -> Vec<u8> {
+  let mut result = Vec::with_capacity(data.len());
+  for cp in data.iter().copied() {
+    if let 0xDC80..=0xDCFF = cp {
+      result.push(cp as u8);
+      continue;
+    }
+    let ch = char::from_u32(cp).unwrap_or(char::REPLACEMENT_CHARACTER);
+    result.extend_from_slice(ch.encode_utf8(&mut [0; 4]).as_bytes());
+  }
+  result
+}
+
+pub fn is_whitespace_unit(unit: u32) -> bool {
+  char::from_u32(unit).is_some_and(char::is_whitespace)
+}
+
+// Number of code units checked for nul at once
+// NOTE: chunk is checked without early exit, so the check compiles to SIMD comparisons
+const NUL_SCAN_CHUNK: usize = 32;
+
+macro_rules! find_nul_impl {
+  ($name:ident, $type:ty) => {
+    /// Returns the position of the first nul character in `data`
+    pub const fn $name(data: &[$type]) -> Option<usize> {
+      let mut rest = data;
+      let mut offset = 0;
+      while let Some((chunk, tail)) = rest.split_first_chunk::<NUL_SCAN_CHUNK>() {
+        let mut found = false;
+        let mut i = 0;
+        while i < NUL_SCAN_CHUNK {
+          found |= chunk[i] == 0;
+          i += 1;
+        }
+        if found {
+          break;
+        }
+        rest = tail;
+        offset += NUL_SCAN_CHUNK;
+      }
+      let mut i = 0;
+      while i < rest.len() {
+        if rest[i] == 0 {
+          return Some(offset + i);
+        }
+        i += 1;
+      }
+      None
+    }
+  };
+}
+
+find_nul_impl!(find_nul_u8, u8);
+find_nul_impl!(find_nul_u16, u16);
+find_nul_impl!(find_nul_u32, u32);
+
+macro_rules! find_nul_ptr_impl {
+  ($name:ident, $type:ty) => {
+    /// Returns the length of a nul-terminated string by reading it unit by unit
+    /// # Safety
+    /// `data` should point to a valid nul-terminated string
+    pub const unsafe fn $name(data: *const $type) -> usize {
+      let mut len = 0;
+      while *data.add(len) != 0 {
+        len += 1;
+      }
+      len
+    }
+  };
+}
+
+find_nul_ptr_impl!(find_nul_ptr_u8, u8);
+find_nul_ptr_impl!(find_nul_ptr_u16, u16);
+find_nul_ptr_impl!(find_nul_ptr_u32, u32);
+
+macro_rules! find_nul_word_impl {
+  ($name:ident, $type:ty) => {
+    /// Returns the length of a nul-terminated string by reading it a few aligned words at a time
+    /// # Safety
+    /// `data` should point to a valid nul-terminated string
+    pub unsafe fn $name(data: *const $type) -> usize {
+      const WORDS: usize = 4;
+      const BLOCK: usize = WORDS * core::mem::size_of::<usize>();
+      const UNITS: usize = BLOCK / core::mem::size_of::<$type>();
+      // Lowest bit of every unit within a word
+      const LO: usize = usize::MAX / <$type>::MAX as usize;
+      // Highest bit of every unit within a word
+      const HI: usize = LO << (<$type>::BITS - 1);
+      let mut ptr = data;
+      while ptr as usize % BLOCK != 0 {
+        if *ptr == 0 {
+          return ptr.offset_from(data) as usize;
+        }
+        ptr = ptr.add(1);
+      }
+      // SAFETY: blocks of words aligned to their size never cross a page boundary, so reading the block
+      // that contains the nul-terminator can not fault even if the rest of the block lies past the end of the string
+      // (same technique as used by C runtime `strlen` implementations)
+      loop {
+        let words = ptr.cast::<[usize; WORDS]>().read();
+        let mut found = 0;
+        for word in words {
+          found |= word.wrapping_sub(LO) & !word & HI;
+        }
+        if found != 0 {
+          break;
+        }
+        ptr = ptr.add(UNITS);
+      }
+      while *ptr != 0 {
+        ptr = ptr.add(1);
+      }
+      ptr.offset_from(data) as usize
+    }
+  };
+}
+
+find_nul_word_impl!(find_nul_word_u8, u8);
+find_nul_word_impl!(find_nul_word_u16, u16);
+find_nul_word_impl!(find_nul_word_u32, u32);
+
+// Finds nul-terminator of a string given a pointer to it
+// NOTE: C runtime `strlen` is used for u8 if `libc` feature is enabled,
+// otherwise strings are read a few aligned words at a time
+pub trait NulScan: Sized {
+  /// # Safety
+  /// `data` should point to a valid nul-terminated string
+  unsafe fn scan_ptr(data: *const Self) -> usize;
+}
+
+#[cfg(feature = "libc")]
+extern "C" {
+  fn strlen(data: *const core::ffi::c_char) -> usize;
+}
+
+#[cfg(feature = "libc")]
+impl NulScan for u8 {
+  unsafe fn scan_ptr(data: *const Self) -> usize {
+    strlen(data.cast())
+  }
+}
+
+#[cfg(not(feature = "libc"))]
+impl NulScan for u8 {
+  unsafe fn scan_ptr(data: *const Self) -> usize {
+    find_nul_word_u8(data)
+  }
+}
+
+impl NulScan for u16 {
+  unsafe fn scan_ptr(data: *const Self) -> usize {
+    find_nul_word_u16(data)
+  }
+}
+
+impl NulScan for u32 {
+  unsafe fn scan_ptr(data: *const Self) -> usize {
+    find_nul_word_u32(data)
+  }
+}
+
+// Encodes rust strings written through `core::fmt::Write`:
+// UTF-8 for u8, UTF-16 (with surrogate pairs) for u16 and UTF-32 for u32
+pub trait FmtUnit: Copy + Sized {
+  fn encoded_len(s: &str) -> usize;
+  fn encode_str(s: &str) -> impl Iterator<Item = Self> + '_;
+}
+
+impl FmtUnit for u8 {
+  fn encoded_len(s: &str) -> usize {
+    s.len()
+  }
+  fn encode_str(s: &str) -> impl Iterator<Item = Self> + '_ {
+    s.bytes()
+  }
+}
+
+impl FmtUnit for u16 {
+  fn encoded_len(s: &str) -> usize {
+    s.chars().map(char::len_utf16).sum()
+  }
+  fn encode_str(s: &str) -> impl Iterator<Item = Self> + '_ {
+    s.encode_utf16()
+  }
+}
+
+impl FmtUnit for u32 {
+  fn encoded_len(s: &str) -> usize {
+    s.chars().count()
+  }
+  fn encode_str(s: &str) -> impl Iterator<Item = Self> + '_ {
+    s.chars().map(|c| c as u32)
+  }
+}
+
+// Encodes a single char using the default encoding of the code unit:
+// UTF-8 for u8, UTF-16 for u16 and UTF-32 for u32
+pub trait EncodeChar: Copy + Default + Sized {
+  // Returns the number of units written to `buf` or None in case of `ch` is unrepresentable
+  fn encode_char(ch: char, buf: &mut [Self; 4]) -> Option<usize>;
+}
+
+impl EncodeChar for u8 {
+  fn encode_char(ch: char, buf: &mut [Self; 4]) -> Option<usize> {
+    Some(ch.encode_utf8(buf).len())
+  }
+}
+
+impl EncodeChar for u16 {
+  fn encode_char(ch: char, buf: &mut [Self; 4]) -> Option<usize> {
+    Some(ch.encode_utf16(buf).len())
+  }
+}
+
+impl EncodeChar for u32 {
+  fn encode_char(ch: char, buf: &mut [Self; 4]) -> Option<usize> {
+    buf[0] = ch as u32;
+    Some(1)
+  }
+}
+
+// Compares code units with a rust string using the default encoding of the code unit:
+// UTF-8 for u8, UTF-16 for u16 and UTF-32 for u32
+pub trait EqStr: Sized {
+  fn eq_str(units: &[Self], s: &str) -> bool;
+}
+
+impl EqStr for u8 {
+  fn eq_str(units: &[Self], s: &str) -> bool {
+    units == s.as_bytes()
+  }
+}
+
+impl EqStr for u16 {
+  fn eq_str(units: &[Self], s: &str) -> bool {
+    s.encode_utf16().eq(units.iter().copied())
+  }
+}
+
+impl EqStr for u32 {
+  fn eq_str(units: &[Self], s: &str) -> bool {
+    s.chars().map(|c| c as u32).eq(units.iter().copied())
+  }
+}
+
+// Case conversions of code units using the default encoding of the code unit,
+// units that can not be decoded or whose mapping is not representable are kept unchanged
+pub trait CaseUnit: Copy + Sized {
+  fn ascii_lower(self) -> Self;
+  fn ascii_upper(self) -> Self;
+  #[cfg(feature = "alloc")]
+  fn convert_case(units: &[Self], upper: bool) -> Vec<Self>;
+  // Yields code points mapped to their simple uppercase form, undecodable units are yielded as is
+  fn fold_case(units: &[Self]) -> impl Iterator<Item = u32> + '_;
+}
+
+fn fold_char(ch: char) -> char {
+  let mut upper = ch.to_uppercase();
+  match upper.len() {
+    1 => upper.next().unwrap_or(ch),
+    _ => ch,
+  }
+}
+
+macro_rules! ascii_case_impl {
+  ($type:ty) => {
+    fn ascii_lower(self) -> Self {
+      match self {
+        0x41..=0x5A => self + 0x20,
+        _ => self,
+      }
+    }
+    fn ascii_upper(self) -> Self {
+      match self {
+        0x61..=0x7A => self - 0x20,
+        _ => self,
+      }
+    }
+  };
+}
+
+impl CaseUnit for u8 {
+  ascii_case_impl!(u8);
+  #[cfg(feature = "alloc")]
+  fn convert_case(units: &[Self], upper: bool) -> Vec<Self> {
+    let mut result = Vec::with_capacity(units.len() + 1);
+    for chunk in units.utf8_chunks() {
+      for ch in chunk.valid().chars() {
+        let mut push = |c: char| result.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+        if upper {
+          ch.to_uppercase().for_each(&mut push)
+        } else {
+          ch.to_lowercase().for_each(&mut push)
+        }
+      }
+      result.extend_from_slice(chunk.invalid());
+    }
+    result
+  }
+  // Bytes that are not valid UTF-8 are yielded as lone surrogates `0xDC80..=0xDCFF`
+  // (same as `encode_u32_lossless`), so that they are never equal to a char
+  fn fold_case(units: &[Self]) -> impl Iterator<Item = u32> + '_ {
+    units.utf8_chunks().flat_map(|chunk| {
+      let valid = chunk.valid().chars().map(|ch| fold_char(ch) as u32);
+      valid.chain(chunk.invalid().iter().map(|byte| 0xDC00 | *byte as u32))
+    })
+  }
+}
+
+impl CaseUnit for u16 {
+  ascii_case_impl!(u16);
+  #[cfg(feature = "alloc")]
+  fn convert_case(units: &[Self], upper: bool) -> Vec<Self> {
+    let mut result = Vec::with_capacity(units.len() + 1);
+    for ch in char::decode_utf16(units.iter().copied()) {
+      match ch {
+        Ok(ch) if upper => ch.to_uppercase().for_each(|c| result.extend_from_slice(c.encode_utf16(&mut [0; 2]))),
+        Ok(ch) => ch.to_lowercase().for_each(|c| result.extend_from_slice(c.encode_utf16(&mut [0; 2]))),
+        Err(err) => result.push(err.unpaired_surrogate()),
+      }
+    }
+    result
+  }
+  fn fold_case(units: &[Self]) -> impl Iterator<Item = u32> + '_ {
+    char::decode_utf16(units.iter().copied()).map(|ch| match ch {
+      Ok(ch) => fold_char(ch) as u32,
+      Err(err) => err.unpaired_surrogate() as u32,
+    })
+  }
+}
+
+impl CaseUnit for u32 {
+  ascii_case_impl!(u32);
+  #[cfg(feature = "alloc")]
+  fn convert_case(units: &[Self], upper: bool) -> Vec<Self> {
+    let mut result = Vec::with_capacity(units.len() + 1);
+    for unit in units.iter().copied() {
+      match char::from_u32(unit) {
+        Some(ch) if upper => result.extend(ch.to_uppercase().map(|c| c as u32)),
+        Some(ch) => result.extend(ch.to_lowercase().map(|c| c as u32)),
+        None => result.push(unit),
+      }
+    }
+    result
+  }
+  fn fold_case(units: &[Self]) -> impl Iterator<Item = u32> + '_ {
+    units.iter().map(|unit| char::from_u32(*unit).map_or(*unit, |ch| fold_char(ch) as u32))
+  }
+}
+
+// Writes code units as a quoted literal, escaping control characters and undecodable units
+pub trait DebugStr: Sized {
+  const PREFIX: &'static str;
+  fn fmt_debug(units: &[Self], f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result;
+}
+
+fn write_escaped(ch: char, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+  use core::fmt::Write;
+  if ch == '\'' {
+    f.write_char(ch)
+  } else {
+    write!(f, "{}", ch.escape_debug())
+  }
+}
+
+impl DebugStr for u8 {
+  const PREFIX: &'static str = "c";
+  fn fmt_debug(units: &[Self], f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    for chunk in units.utf8_chunks() {
+      for ch in chunk.valid().chars() {
+        write_escaped(ch, f)?;
+      }
+      for byte in chunk.invalid() {
+        write!(f, "\\x{:02x}", byte)?;
+      }
+    }
+    Ok(())
+  }
+}
+
+impl DebugStr for u16 {
+  const PREFIX: &'static str = "u16c";
+  fn fmt_debug(units: &[Self], f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    for ch in char::decode_utf16(units.iter().copied()) {
+      match ch {
+        Ok(ch) => write_escaped(ch, f)?,
+        Err(err) => write!(f, "\\u{{{:x}}}", err.unpaired_surrogate())?,
+      }
+    }
+    Ok(())
+  }
+}
+
+impl DebugStr for u32 {
+  const PREFIX: &'static str = "u32c";
+  fn fmt_debug(units: &[Self], f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    for unit in units.iter().copied() {
+      match char::from_u32(unit) {
+        Some(ch) => write_escaped(ch, f)?,
+        None => write!(f, "\\u{{{:x}}}", unit)?,
+      }
+    }
+    Ok(())
+  }
+}
+
+pub struct DebugUnits<'a, T>(pub &'a [T]);
+
+impl<'a, T: DebugStr> core::fmt::Debug for DebugUnits<'a, T> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    write!(f, "{}\"", T::PREFIX)?;
+    T::fmt_debug(self.0, f)?;
+    f.write_str("\"")
+  }
+}
+
 // A const implementation of https://github.com/rust-lang/rust/blob/d902752866cbbdb331e3cf28ff6bba86ab0f6c62/library/core/src/str/mod.rs#L509-L537
 // Assumes `utf8` is a valid &str
 pub const unsafe fn next_code_point(utf8: &[u8]) -> Option<(u32, &[u8])> {
@@ -92,6 +597,53 @@ pub const fn length_as_utf8(bytes: &[u8]) -> usize {
   bytes.len()
 }
 
+// A const implementation of `char::encode_utf16` returning code units and their count
+pub const fn encode_utf16_units(ch: u32) -> ([u16; 2], usize) {
+  if (ch & 0xFFFF) == ch {
+    ([ch as u16, 0], 1)
+  } else {
+    let ch = ch - 0x10000;
+    ([0xD800 | (ch >> 10) as u16, 0xDC00 | (ch & 0x3FF) as u16], 2)
+  }
+}
+
+// Const encoders of a single char returning code units and their count
+// NOTE: None is returned for chars unrepresentable in target encoding
+pub const fn encode_const_u8(ch: u32) -> Option<([u8; 4], usize)> {
+  // A const implementation of `char::encode_utf8`
+  Some(match ch {
+    0..=0x7F => ([ch as u8, 0, 0, 0], 1),
+    0x80..=0x7FF => ([0xC0 | (ch >> 6) as u8, 0x80 | (ch & 0x3F) as u8, 0, 0], 2),
+    0x800..=0xFFFF => (
+      [
+        0xE0 | (ch >> 12) as u8,
+        0x80 | ((ch >> 6) & 0x3F) as u8,
+        0x80 | (ch & 0x3F) as u8,
+        0,
+      ],
+      3,
+    ),
+    _ => (
+      [
+        0xF0 | (ch >> 18) as u8,
+        0x80 | ((ch >> 12) & 0x3F) as u8,
+        0x80 | ((ch >> 6) & 0x3F) as u8,
+        0x80 | (ch & 0x3F) as u8,
+      ],
+      4,
+    ),
+  })
+}
+
+pub const fn encode_const_u16(ch: u32) -> Option<([u16; 4], usize)> {
+  let (units, count) = encode_utf16_units(ch);
+  Some(([units[0], units[1], 0, 0], count))
+}
+
+pub const fn encode_const_u32(ch: u32) -> Option<([u32; 4], usize)> {
+  Some(([ch, 0, 0, 0], 1))
+}
+
 pub const unsafe fn length_as_u16(mut bytes: &[u8]) -> Result<usize, usize> {
   let mut len = 0;
   while let Some((ch, rest)) = next_code_point(bytes) {
@@ -148,3 +700,52 @@ pub const unsafe fn length_as_u32_or_panic(mut bytes: &[u8]) -> usize {
   }
   len
 }
+
+#[cfg(test)]
+mod tests {
+  use super::{find_nul_u16, find_nul_u32, find_nul_u8, NulScan};
+
+  fn naive<T: Copy + Default + PartialEq>(data: &[T]) -> Option<usize> {
+    data.iter().position(|c| *c == T::default())
+  }
+
+  #[test]
+  fn finds_nul_in_slices() {
+    for len in [0, 1, 31, 32, 33, 64, 100] {
+      let mut data = vec![1u16; 130];
+      data[len] = 0;
+      assert_eq!(find_nul_u16(&data), naive(&data));
+      assert_eq!(find_nul_u16(&data[..len]), None);
+      let data32: Vec<u32> = data.iter().map(|c| *c as u32).collect();
+      assert_eq!(find_nul_u32(&data32), Some(len));
+      let data8: Vec<u8> = data.iter().map(|c| *c as u8).collect();
+      assert_eq!(find_nul_u8(&data8), Some(len));
+    }
+  }
+  #[test]
+  fn scans_pointers_at_any_alignment() {
+    // Units with the lowest and the highest bits set must not be taken for nul within a word
+    for (fill16, fill32) in [(0x0100u16, 0x0001_0000u32), (0x8001, 0x8000_0001)] {
+      let mut data = [fill16; 64];
+      let mut data32 = [fill32; 64];
+      for start in 0..16 {
+        for len in 0..40 {
+          data[start + len] = 0;
+          data32[start + len] = 0;
+          assert_eq!(unsafe { u16::scan_ptr(data.as_ptr().add(start)) }, len);
+          assert_eq!(unsafe { u32::scan_ptr(data32.as_ptr().add(start)) }, len);
+          data[start + len] = fill16;
+          data32[start + len] = fill32;
+        }
+      }
+    }
+    assert_eq!(unsafe { u8::scan_ptr(c"abc".as_ptr().cast()) }, 3);
+  }
+  #[test]
+  fn scans_pointers_in_const_context() {
+    use crate::strings::U16CStr;
+    const DATA: [u16; 4] = [b'a' as u16, b'b' as u16, 0, b'c' as u16];
+    const LEN: usize = unsafe { U16CStr::from_ptr(DATA.as_ptr()) }.len_usize();
+    assert_eq!(LEN, 2);
+  }
+}
@@ -0,0 +1,219 @@
+use super::{StrError, U16CStr, U32CStr, U8CStr};
+
+/// Borrowed C string with the length calculated once on construction,
+/// so that length and slice accessors do not rescan for nul-terminator on every call
+/// NOTE: all other methods of the borrowed cstr are available through `Deref`
+pub struct MeasuredCStr<'a, T> {
+  // Contents including nul-terminator
+  data: &'a [T],
+}
+
+impl<'a, T> Clone for MeasuredCStr<'a, T> {
+  fn clone(&self) -> Self {
+    *self
+  }
+}
+
+impl<'a, T> Copy for MeasuredCStr<'a, T> {}
+
+impl<'a, T> MeasuredCStr<'a, T> {
+  /// Checks wheither the str is empty
+  pub const fn is_empty(&self) -> bool {
+    self.len_usize() == 0
+  }
+  /// Returns the cached length of the str
+  pub const fn len_usize(&self) -> usize {
+    self.data.len() - 1
+  }
+  /// Returns the cached length of the str
+  /// NOTE: this method returns value as DWORD
+  pub const fn len_dword(&self) -> u32 {
+    self.len_usize() as u32
+  }
+  /// Returns the cached length of the str
+  /// NOTE: This method casts the result to the desired type via TryInto trait
+  pub fn len<N: TryFrom<usize> + Default>(&self) -> N {
+    self.len_usize().try_into().unwrap_or_default()
+  }
+  /// Returns the cached length of the str including nul-terminator
+  pub const fn len_with_nul_usize(&self) -> usize {
+    self.data.len()
+  }
+  /// Returns the cached length of the str including nul-terminator
+  /// NOTE: this method returns value as DWORD
+  pub const fn len_with_nul_dword(&self) -> u32 {
+    self.data.len() as u32
+  }
+  /// Returns the cached length of the str including nul-terminator
+  /// NOTE: This method casts the result to the desired type via TryInto trait
+  pub fn len_with_nul<N: TryFrom<usize> + Default>(&self) -> N {
+    self.data.len().try_into().unwrap_or_default()
+  }
+  /// Calculates the size in bytes of contents including nul-terminator character
+  pub const fn sizeof_usize(&self) -> usize {
+    core::mem::size_of_val(self.data)
+  }
+  /// Calculates the size in bytes of contents including nul-terminator character
+  /// NOTE: this method returns value as DWORD
+  pub const fn sizeof_dword(&self) -> u32 {
+    self.sizeof_usize() as u32
+  }
+  /// Calculates the size in bytes of contents including nul-terminator character
+  /// NOTE: This method casts the result to the desired type via TryInto trait
+  pub fn sizeof<N: TryFrom<usize> + Default>(&self) -> N {
+    self.sizeof_usize().try_into().unwrap_or_default()
+  }
+  /// Returns slice representation until nul-terminator (and excluding it)
+  pub fn as_slice(&self) -> &'a [T] {
+    &self.data[..self.data.len() - 1]
+  }
+  /// Returns slice representation until nul-terminator (and including it)
+  pub const fn as_slice_with_nul(&self) -> &'a [T] {
+    self.data
+  }
+  /// Returns a const pointer to the underlying data
+  pub const fn as_ptr(&self) -> *const T {
+    self.data.as_ptr()
+  }
+  /// Returns an iterator over characters of the str until nul-terminator
+  pub fn iter(&self) -> core::slice::Iter<'a, T> {
+    self.as_slice().iter()
+  }
+}
+
+impl<'a, T: Copy> MeasuredCStr<'a, T> {
+  /// Returns the character at a given index
+  pub fn get(&self, index: usize) -> Option<T> {
+    self.as_slice().get(index).copied()
+  }
+}
+
+macro_rules! measured_cstr_impls {
+  ($type:ty, $cstr:ident) => {
+    impl<'a> MeasuredCStr<'a, $type> {
+      /// Measures the length of `cstr` once
+      pub const fn new(cstr: &'a $cstr) -> Self {
+        Self { data: cstr.as_slice_with_nul() }
+      }
+      /// Constructs an instance given a pointer to a nul-terminated string
+      /// NOTE: lifetime of the returned value is inferred from context
+      /// # Safety
+      /// `data` should point to a valid nul-terminated string
+      pub unsafe fn from_ptr(data: *const $type) -> Self {
+        Self::new($cstr::from_ptr(data))
+      }
+      /// Constructs an instance given a pointer to a string of at most `max_len` characters
+      /// NOTE: this function returns Err in case of nul-terminator was not found within `max_len` characters
+      /// # Safety
+      /// `data` should point to a valid memory of at least `max_len` characters
+      pub unsafe fn from_ptr_n(data: *const $type, max_len: usize) -> Result<Self, StrError> {
+        Ok(Self::new($cstr::from_ptr_n(data, max_len)?))
+      }
+      /// Constructs an instance given a pointer to a string of known length, without scanning it
+      /// # Safety
+      /// `data` should point to a valid memory of `len` characters followed by nul-terminator
+      /// and should not contain nul characters before it
+      pub unsafe fn from_ptr_with_len(data: *const $type, len: usize) -> Self {
+        Self { data: core::slice::from_raw_parts(data, len + 1) }
+      }
+      /// Converts back to borrowed cstr
+      pub const fn as_cstr(&self) -> &'a $cstr {
+        unsafe { $cstr::from_slice_unchecked(self.data) }
+      }
+    }
+
+    impl<'a> core::ops::Deref for MeasuredCStr<'a, $type> {
+      type Target = $cstr;
+      fn deref(&self) -> &$cstr {
+        self.as_cstr()
+      }
+    }
+
+    impl<'a> AsRef<$cstr> for MeasuredCStr<'a, $type> {
+      fn as_ref(&self) -> &$cstr {
+        self.as_cstr()
+      }
+    }
+
+    impl<'a> From<&'a $cstr> for MeasuredCStr<'a, $type> {
+      fn from(value: &'a $cstr) -> Self {
+        Self::new(value)
+      }
+    }
+
+    impl<'a> From<MeasuredCStr<'a, $type>> for &'a $cstr {
+      fn from(value: MeasuredCStr<'a, $type>) -> Self {
+        value.as_cstr()
+      }
+    }
+
+    impl<'a, 'b> PartialEq<MeasuredCStr<'b, $type>> for MeasuredCStr<'a, $type> {
+      fn eq(&self, other: &MeasuredCStr<'b, $type>) -> bool {
+        self.as_slice() == other.as_slice()
+      }
+    }
+
+    impl<'a> Eq for MeasuredCStr<'a, $type> {}
+
+    impl<'a> PartialEq<$cstr> for MeasuredCStr<'a, $type> {
+      fn eq(&self, other: &$cstr) -> bool {
+        self.as_slice() == other.as_slice()
+      }
+    }
+
+    impl<'a> PartialEq<str> for MeasuredCStr<'a, $type> {
+      fn eq(&self, other: &str) -> bool {
+        *self.as_cstr() == *other
+      }
+    }
+
+    impl<'a, 'b> PartialEq<&'b str> for MeasuredCStr<'a, $type> {
+      fn eq(&self, other: &&'b str) -> bool {
+        *self.as_cstr() == **other
+      }
+    }
+
+    impl<'a> core::fmt::Debug for MeasuredCStr<'a, $type> {
+      fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Debug::fmt(self.as_cstr(), f)
+      }
+    }
+  };
+}
+
+measured_cstr_impls!(u8, U8CStr);
+measured_cstr_impls!(u16, U16CStr);
+measured_cstr_impls!(u32, U32CStr);
+
+#[cfg(test)]
+mod tests {
+  use super::MeasuredCStr;
+  use crate::strings::{StrError, U16CString, U8CStr};
+
+  #[test]
+  fn caches_length() {
+    let data = b"abc\0def\0";
+    let measured = unsafe { MeasuredCStr::<u8>::from_ptr(data.as_ptr()) };
+    assert_eq!(measured.len_usize(), 3);
+    assert_eq!(measured.len_with_nul::<u8>(), 4);
+    assert_eq!(measured.sizeof_dword(), 4);
+    assert_eq!(measured.as_slice(), b"abc");
+    assert_eq!(measured.get(1), Some(b'b'));
+    assert_eq!(measured, "abc");
+    // methods of the borrowed cstr are available through Deref
+    assert!(measured.starts_with(b"ab"));
+    let cstr: &U8CStr = measured.into();
+    assert_eq!(cstr.as_slice_with_nul(), b"abc\0");
+  }
+  #[test]
+  fn constructs_wide() {
+    let string = U16CString::encode("\u{1F600}x").unwrap();
+    let measured = MeasuredCStr::from(string.as_ref());
+    assert_eq!(measured.sizeof_usize(), 8);
+    let known = unsafe { MeasuredCStr::<u16>::from_ptr_with_len(string.as_ptr(), 3) };
+    assert_eq!(known, measured);
+    assert_eq!(format!("{:?}", known), "u16c\"\u{1F600}x\"");
+    let bounded = unsafe { MeasuredCStr::<u16>::from_ptr_n(string.as_ptr(), 3) };
+    assert_eq!(bounded, Err(StrError::NulNotFound));
+  }
+}
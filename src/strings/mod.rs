@@ -1,34 +1,106 @@
+mod allocator;
 mod common;
+mod case_insensitive;
 mod cstr;
+#[cfg(feature = "alloc")]
+mod cstr_array;
+#[cfg(feature = "alloc")]
+mod env_block;
+mod measured;
+mod multi;
+mod owning;
 mod str_macro;
+#[cfg(feature = "alloc")]
 mod string_macro;
 mod static_str_macro;
 mod u16cstr;
 mod u32cstr;
 mod writes;
-use std::{ffi::{OsString, OsStr}, os::windows::prelude::{OsStringExt, OsStrExt}};
 
+pub use allocator::{CAllocator, LibcAlloc};
+pub use case_insensitive::CaseInsensitive;
 pub use cstr::*;
+#[cfg(feature = "alloc")]
+pub use cstr_array::CStrArray;
+#[cfg(feature = "alloc")]
+pub use env_block::EnvBlock;
+pub use measured::MeasuredCStr;
+pub use multi::*;
+pub use owning::{CDeleter, ExternCDeleter, FnDeleter, LibcFree, NoopDeleter, OwningCStr};
 pub use u16cstr::*;
 pub use u32cstr::*;
 pub use writes::*;
 
-pub fn encode<T: CStrCharType>(data: &str) -> Option<T> {
+#[cfg(feature = "alloc")]
+use crate::prelude::*;
+
+pub fn encode<T: CStrCharType>(data: &str) -> Result<T, StrError> {
   T::encode(data)
 }
 
 pub trait CStrCharType {
   type Char;
-  fn encode(_data: &str) -> Option<Self> where Self: Sized {
+  fn encode(_data: &str) -> Result<Self, StrError> where Self: Sized {
     unimplemented!("Encoding is not implemented for this type")
   }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum StrError {
+  /// Source does not contain nul-terminator
   NulNotFound,
+  /// Source contains nul character at position `pos` where it is not allowed
+  InteriorNul { pos: usize },
+  /// Source requires `needed` characters (excluding nul-terminator) but only `capacity` is avaliable
+  /// NOTE: for sources of unknown length `needed` is the lower bound
+  CapacityExceeded { needed: usize, capacity: usize },
+  /// Char at byte `index` of source is unrepresentable in target encoding
+  InvalidCodePoint { index: usize },
+  /// Item at `index` of a multi-string list is empty, which would terminate the list early
+  EmptyItem { index: usize },
+}
+
+#[cfg(feature = "alloc")]
+/// An error returned by `U*CString::new` in case of provided data contains nul character.
+/// Gives back the original vector
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InteriorNulError<T> {
+  pos: usize,
+  inner: Vec<T>,
+}
+
+#[cfg(feature = "alloc")]
+impl<T> InteriorNulError<T> {
+  pub(crate) fn new(pos: usize, inner: Vec<T>) -> Self {
+    Self { pos, inner }
+  }
+  /// Returns the position of the nul character in the source
+  pub fn nul_position(&self) -> usize {
+    self.pos
+  }
+  /// Returns the vector that was passed to constructor
+  pub fn into_vec(self) -> Vec<T> {
+    self.inner
+  }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> From<InteriorNulError<T>> for StrError {
+  fn from(value: InteriorNulError<T>) -> Self {
+    StrError::InteriorNul { pos: value.pos }
+  }
 }
 
+#[cfg(feature = "alloc")]
+impl<T> core::fmt::Display for InteriorNulError<T> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    write!(f, "Nul character found in source at position {}", self.pos)
+  }
+}
+
+#[cfg(feature = "std")]
+impl<T: core::fmt::Debug> std::error::Error for InteriorNulError<T> {}
+
 /// Alias for [`U16CStr`] or [`U32CStr`] depending on platform. Intended to match typical C
 /// `wchar_t` size on platform.
 #[cfg(not(windows))]
@@ -39,11 +111,13 @@ pub type WideCStr = U32CStr;
 #[cfg(windows)]
 pub type WideCStr = U16CStr;
 
+#[cfg(feature = "alloc")]
 /// Alias for [`U16CString`] or [`U32CString`] depending on platform. Intended to match typical C
 /// `wchar_t` size on platform.
 #[cfg(not(windows))]
 pub type WideCString = U32CString;
 
+#[cfg(feature = "alloc")]
 /// Alias for [`U16CString`] or [`U32CString`] depending on platform. Intended to match typical C
 /// `wchar_t` size on platform.
 #[cfg(windows)]
@@ -59,40 +133,65 @@ pub type StaticWideCStr<const CAPACITY: usize> = StaticU32CStr<CAPACITY>;
 #[cfg(windows)]
 pub type StaticWideCStr<const CAPACITY: usize> = StaticU16CStr<CAPACITY>;
 
-impl WideCStr {
-  pub fn to_os_string(&self) -> OsString {
-    OsString::from_wide(self.as_slice())
-  }
-}
+/// Alias for [`U16MultiCStr`] or [`U32MultiCStr`] depending on platform. Intended to match typical C
+/// `wchar_t` size on platform.
+#[cfg(not(windows))]
+pub type WideMultiCStr = U32MultiCStr;
 
-impl WideCString {
-  pub fn to_os_string(&self) -> OsString {
-    OsString::from_wide(self.as_slice())
-  }
-}
+/// Alias for [`U16MultiCStr`] or [`U32MultiCStr`] depending on platform. Intended to match typical C
+/// `wchar_t` size on platform.
+#[cfg(windows)]
+pub type WideMultiCStr = U16MultiCStr;
 
-impl From<OsString> for WideCString {
-  fn from(value: OsString) -> Self {
-    let inner: Vec<u16> = value.encode_wide().collect();
-    Self::from(inner)
-  }
-}
+#[cfg(feature = "alloc")]
+/// Alias for [`U16MultiCString`] or [`U32MultiCString`] depending on platform. Intended to match typical C
+/// `wchar_t` size on platform.
+#[cfg(not(windows))]
+pub type WideMultiCString = U32MultiCString;
 
-impl From<&OsStr> for WideCString {
-  fn from(value: &OsStr) -> Self {
-    let inner: Vec<u16> = value.encode_wide().collect();
-    Self::from(inner)
-  }
-}
+#[cfg(feature = "alloc")]
+/// Alias for [`U16MultiCString`] or [`U32MultiCString`] depending on platform. Intended to match typical C
+/// `wchar_t` size on platform.
+#[cfg(windows)]
+pub type WideMultiCString = U16MultiCString;
 
 impl core::fmt::Display for StrError {
   fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-    f.write_str("Nul-terminator was not found in source")
+    match self {
+      StrError::NulNotFound => f.write_str("Nul-terminator was not found in source"),
+      StrError::InteriorNul { pos } => {
+        write!(f, "Nul character found in source at position {}", pos)
+      }
+      StrError::CapacityExceeded { needed, capacity } => write!(
+        f,
+        "Source requires {} characters, but capacity is {}",
+        needed, capacity
+      ),
+      StrError::InvalidCodePoint { index } => write!(
+        f,
+        "Character at index {} is unrepresentable in target encoding",
+        index
+      ),
+      StrError::EmptyItem { index } => write!(
+        f,
+        "Item at index {} is empty and can not be stored in a multi-string list",
+        index
+      ),
+    }
   }
 }
 
+#[cfg(feature = "std")]
+impl std::error::Error for StrError {}
+
 #[cfg(feature = "widestring")]
 mod widestr_convs;
 
+#[cfg(feature = "std")]
+mod os_convs;
+
+#[cfg(feature = "serde")]
+pub mod serde_units;
+
 #[doc(hidden)]
 pub mod internals;
@@ -0,0 +1,290 @@
+use super::{StrError, U16CStr, U32CStr, U8CStr};
+#[cfg(feature = "alloc")]
+use super::{CStrCharType, U16CString, U32CString, U8CString};
+#[cfg(feature = "alloc")]
+use crate::prelude::*;
+
+macro_rules! multicstr_impls {
+  ($multi:ident, $owned:ident, $iter:ident, $type:ty, $cstr:ident, $cstring:ident) => {
+    #[doc = concat!("
+    Borrowed list of nul-terminated strings ending with an empty string (double nul),
+    e.g. `REG_MULTI_SZ` values or environment blocks. Owned version is [`", stringify!($owned), "`]
+    NOTE: the underlying slice always ends with the nul-terminator of the list
+    ")]
+    #[repr(transparent)]
+    pub struct $multi([$type]);
+
+    impl $multi {
+      /// Returns the length of the list (including its terminator) stored at the beginning of `data`
+      fn list_len(data: &[$type]) -> Option<usize> {
+        let mut start = 0;
+        loop {
+          let len = data.get(start..)?.iter().position(|c| *c == 0)?;
+          if len == 0 {
+            return Some(start + 1);
+          }
+          start += len + 1;
+        }
+      }
+      /// Constructs a list from the beginning of the slice
+      /// NOTE: this function returns Err in case of `data` does not contain the terminating empty string
+      pub fn try_from_slice(data: &[$type]) -> Result<&Self, StrError> {
+        let len = Self::list_len(data).ok_or(StrError::NulNotFound)?;
+        Ok(unsafe { Self::from_slice_unchecked(&data[..len]) })
+      }
+      /// Constructs a list given a slice
+      /// # Safety
+      /// provided slice should end with the first empty string in it
+      pub const unsafe fn from_slice_unchecked(data: &[$type]) -> &Self {
+        core::mem::transmute(data)
+      }
+      /// Constructs a list given a pointer to a double nul-terminated buffer
+      #[doc = concat!("NOTE: this function can be dangerous because of not constaining length,
+      consider using safer funtion: `", stringify!($multi), "::from_ptr_n`")]
+      /// NOTE: lifetime of the returned value is inferred from context
+      /// # Safety
+      /// `data` should point to a valid memory where the list is stored
+      pub unsafe fn from_ptr<'a>(data: *const $type) -> &'a Self {
+        let mut len = 0;
+        loop {
+          let item_len = <$type as $crate::strings::internals::NulScan>::scan_ptr(data.add(len));
+          len += item_len + 1;
+          if item_len == 0 {
+            break;
+          }
+        }
+        Self::from_slice_unchecked(core::slice::from_raw_parts(data, len))
+      }
+      /// Constructs a list given a pointer to a buffer of `n` characters
+      /// NOTE: this function returns Err in case of the list is not terminated within `n` characters
+      /// NOTE: lifetime of the returned value is inferred from context
+      /// # Safety
+      /// `data` should point to a valid memory of at least `n` characters
+      pub unsafe fn from_ptr_n<'a>(data: *const $type, n: usize) -> Result<&'a Self, StrError> {
+        Self::try_from_slice(core::slice::from_raw_parts(data, n))
+      }
+      /// Returns the raw buffer of the list including the terminating nul
+      pub const fn as_slice_with_nul(&self) -> &[$type] {
+        &self.0
+      }
+      /// Returns a const pointer to the underlying data
+      pub const fn as_ptr(&self) -> *const $type {
+        self.0.as_ptr()
+      }
+      /// Checks whether the list does not contain any strings
+      pub fn is_empty(&self) -> bool {
+        self.0.first().map_or(true, |c| *c == 0)
+      }
+      /// Returns an iterator over strings of the list
+      pub fn iter(&self) -> $iter<'_> {
+        $iter { rest: &self.0 }
+      }
+    }
+
+    /// Iterator over strings of a multi-string list
+    pub struct $iter<'a> {
+      rest: &'a [$type],
+    }
+
+    impl<'a> Iterator for $iter<'a> {
+      type Item = &'a $cstr;
+      fn next(&mut self) -> Option<Self::Item> {
+        let len = self.rest.iter().position(|c| *c == 0)?;
+        if len == 0 {
+          return None;
+        }
+        let (item, rest) = self.rest.split_at(len + 1);
+        self.rest = rest;
+        Some(unsafe { $cstr::from_slice_unchecked(item) })
+      }
+    }
+
+    impl<'a> IntoIterator for &'a $multi {
+      type Item = &'a $cstr;
+      type IntoIter = $iter<'a>;
+      fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+      }
+    }
+
+    impl PartialEq for $multi {
+      fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+      }
+    }
+
+    impl Eq for $multi {}
+
+    impl core::fmt::Debug for $multi {
+      fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+      }
+    }
+
+    #[cfg(feature = "alloc")]
+    impl ToOwned for $multi {
+      type Owned = $owned;
+      fn to_owned(&self) -> Self::Owned {
+        let mut data = self.0.to_vec();
+        if data.len() == 1 {
+          data.push(0);
+        }
+        $owned(data)
+      }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[doc = concat!("
+    Owned list of nul-terminated strings ending with an empty string (double nul).
+    Borrowed version is [`", stringify!($multi), "`]
+    NOTE: empty list is stored as two nul characters
+    ")]
+    #[derive(Clone, PartialEq, Eq)]
+    pub struct $owned(Vec<$type>);
+
+    #[cfg(feature = "alloc")]
+    impl Default for $owned {
+      fn default() -> Self {
+        Self(vec![0, 0])
+      }
+    }
+
+    #[cfg(feature = "alloc")]
+    impl $owned {
+      /// Constructs an empty list
+      pub fn new() -> Self {
+        Self::default()
+      }
+      /// Encodes every string of `items` and constructs a list of them
+      /// NOTE: this function returns Err in case of an item is empty, contains nul
+      /// or is unrepresentable in the target encoding
+      pub fn encode<S: AsRef<str>>(items: impl IntoIterator<Item = S>) -> Result<Self, StrError> {
+        let mut result = Self::default();
+        for item in items {
+          result.push(&<$cstring as CStrCharType>::encode(item.as_ref())?)?;
+        }
+        Ok(result)
+      }
+      /// Constructs a list by copying `items`
+      /// NOTE: this function returns Err in case of an item is empty
+      pub fn from_cstrs<S: AsRef<$cstr>>(items: impl IntoIterator<Item = S>) -> Result<Self, StrError> {
+        let mut result = Self::default();
+        for item in items {
+          result.push(item.as_ref())?;
+        }
+        Ok(result)
+      }
+      /// Appends a copy of `item` to the end of the list
+      /// NOTE: this method returns Err in case of `item` is empty
+      pub fn push(&mut self, item: &$cstr) -> Result<(), StrError> {
+        let data = item.as_slice();
+        if data.is_empty() {
+          return Err(StrError::EmptyItem { index: self.iter().count() });
+        }
+        if self.is_empty() {
+          self.0.clear();
+        } else {
+          self.0.pop();
+        }
+        self.0.extend_from_slice(data);
+        self.0.extend_from_slice(&[0, 0]);
+        Ok(())
+      }
+      /// Returns the raw double nul-terminated buffer
+      pub fn into_vec(self) -> Vec<$type> {
+        self.0
+      }
+    }
+
+    #[cfg(feature = "alloc")]
+    impl core::ops::Deref for $owned {
+      type Target = $multi;
+      fn deref(&self) -> &Self::Target {
+        unsafe { $multi::from_slice_unchecked(&self.0) }
+      }
+    }
+
+    #[cfg(feature = "alloc")]
+    impl AsRef<$multi> for $owned {
+      fn as_ref(&self) -> &$multi {
+        self
+      }
+    }
+
+    #[cfg(feature = "alloc")]
+    impl core::borrow::Borrow<$multi> for $owned {
+      fn borrow(&self) -> &$multi {
+        self
+      }
+    }
+
+    #[cfg(feature = "alloc")]
+    impl<'a> IntoIterator for &'a $owned {
+      type Item = &'a $cstr;
+      type IntoIter = $iter<'a>;
+      fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+      }
+    }
+
+    #[cfg(feature = "alloc")]
+    impl core::fmt::Debug for $owned {
+      fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Debug::fmt(&**self, f)
+      }
+    }
+  };
+}
+
+multicstr_impls!(U8MultiCStr, U8MultiCString, U8MultiCStrIter, u8, U8CStr, U8CString);
+multicstr_impls!(U16MultiCStr, U16MultiCString, U16MultiCStrIter, u16, U16CStr, U16CString);
+multicstr_impls!(U32MultiCStr, U32MultiCString, U32MultiCStrIter, u32, U32CStr, U32CString);
+
+#[cfg(test)]
+mod tests {
+  use super::{U16MultiCStr, U16MultiCString, U8MultiCStr, U8MultiCString};
+  use crate::strings::{StrError, U8CString};
+
+  #[test]
+  fn builds_double_nul_buffer() {
+    let list = U8MultiCString::encode(["ab", "c"]).unwrap();
+    assert_eq!(list.as_slice_with_nul(), b"ab\0c\0\0");
+    let empty = U8MultiCString::new();
+    assert_eq!(empty.as_slice_with_nul(), b"\0\0");
+    assert!(empty.is_empty());
+    assert_eq!(U8MultiCString::encode(["a", "", "b"]), Err(StrError::EmptyItem { index: 1 }));
+    assert_eq!(U8MultiCString::encode(["a\0b"]), Err(StrError::InteriorNul { pos: 1 }));
+  }
+  #[test]
+  fn parses_slice() {
+    let list = U8MultiCStr::try_from_slice(b"ab\0c\0\0garbage").unwrap();
+    assert_eq!(list.as_slice_with_nul(), b"ab\0c\0\0");
+    let items: Vec<_> = list.iter().collect();
+    assert_eq!(items, ["ab", "c"]);
+    assert_eq!(U8MultiCStr::try_from_slice(b"ab\0c\0"), Err(StrError::NulNotFound));
+    assert_eq!(U8MultiCStr::try_from_slice(b"ab"), Err(StrError::NulNotFound));
+    assert!(U8MultiCStr::try_from_slice(b"\0").unwrap().is_empty());
+  }
+  #[test]
+  fn parses_pointer() {
+    let list = U16MultiCString::encode(["dev1", "dev2"]).unwrap();
+    let parsed = unsafe { U16MultiCStr::from_ptr(list.as_ptr()) };
+    assert_eq!(parsed, &*list);
+    assert_eq!(parsed.iter().count(), 2);
+    let bounded = unsafe { U16MultiCStr::from_ptr_n(list.as_ptr(), 6) };
+    assert_eq!(bounded.unwrap_err(), StrError::NulNotFound);
+    let empty = [0u16];
+    assert!(unsafe { U16MultiCStr::from_ptr(empty.as_ptr()) }.is_empty());
+    let narrow = unsafe { U8MultiCStr::from_ptr(b"ab\0c\0\0garbage".as_ptr()) };
+    assert_eq!(narrow.as_slice_with_nul(), b"ab\0c\0\0");
+  }
+  #[test]
+  fn round_trips() {
+    let items = [U8CString::from_slice(b"x=1"), U8CString::from_slice(b"y=2")];
+    let mut list = U8MultiCString::from_cstrs(&items).unwrap();
+    list.push(&U8CString::from_slice(b"z=3")).unwrap();
+    let owned = U8MultiCStr::try_from_slice(list.as_slice_with_nul()).unwrap().to_owned();
+    assert_eq!(owned, list);
+    assert_eq!(format!("{:?}", owned), "[c\"x=1\", c\"y=2\", c\"z=3\"]");
+  }
+}
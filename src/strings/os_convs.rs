@@ -0,0 +1,136 @@
+use std::ffi::{OsStr, OsString};
+
+#[cfg(windows)]
+use std::os::windows::prelude::{OsStrExt, OsStringExt};
+
+#[cfg(unix)]
+use std::os::unix::prelude::{OsStrExt, OsStringExt};
+
+use super::{WideCStr, WideCString};
+
+#[cfg(windows)]
+impl WideCStr {
+  pub fn to_os_string(&self) -> OsString {
+    OsString::from_wide(self.as_slice())
+  }
+}
+
+#[cfg(windows)]
+impl WideCString {
+  pub fn to_os_string(&self) -> OsString {
+    OsString::from_wide(self.as_slice())
+  }
+}
+
+#[cfg(windows)]
+impl From<OsString> for WideCString {
+  fn from(value: OsString) -> Self {
+    let inner: Vec<u16> = value.encode_wide().collect();
+    Self::from(inner)
+  }
+}
+
+#[cfg(windows)]
+impl From<&OsStr> for WideCString {
+  fn from(value: &OsStr) -> Self {
+    let inner: Vec<u16> = value.encode_wide().collect();
+    Self::from(inner)
+  }
+}
+
+#[cfg(unix)]
+impl WideCStr {
+  /// Converts UTF-32 contents to UTF-8 encoded `OsString`
+  /// NOTE: code units in range `0xDC80..=0xDCFF` are written back as raw bytes,
+  /// other invalid code points are replaced with U+FFFD
+  pub fn to_os_string(&self) -> OsString {
+    OsString::from_vec(super::internals::decode_u32_lossless(self.as_slice()))
+  }
+}
+
+#[cfg(unix)]
+impl WideCString {
+  /// Converts UTF-32 contents to UTF-8 encoded `OsString`
+  /// NOTE: code units in range `0xDC80..=0xDCFF` are written back as raw bytes,
+  /// other invalid code points are replaced with U+FFFD
+  pub fn to_os_string(&self) -> OsString {
+    self.as_ref().to_os_string()
+  }
+}
+
+#[cfg(unix)]
+impl From<OsString> for WideCString {
+  fn from(value: OsString) -> Self {
+    Self::from(value.as_os_str())
+  }
+}
+
+#[cfg(unix)]
+impl From<&OsStr> for WideCString {
+  /// NOTE: bytes that are not valid UTF-8 are stored as code units in range `0xDC80..=0xDCFF`,
+  /// so converting back with `to_os_string` restores the original bytes
+  fn from(value: &OsStr) -> Self {
+    Self::from(super::internals::encode_u32_lossless(value.as_bytes()))
+  }
+}
+
+#[cfg(unix)]
+impl super::U8CStr {
+  /// Returns the contents until nul-terminator as `OsStr` without copying
+  pub fn as_os_str(&self) -> &OsStr {
+    OsStr::from_bytes(self.as_slice())
+  }
+  pub fn to_os_string(&self) -> OsString {
+    self.as_os_str().to_os_string()
+  }
+}
+
+#[cfg(unix)]
+impl AsRef<OsStr> for super::U8CStr {
+  fn as_ref(&self) -> &OsStr {
+    self.as_os_str()
+  }
+}
+
+#[cfg(unix)]
+impl From<OsString> for super::U8CString {
+  fn from(value: OsString) -> Self {
+    Self::from(value.into_vec())
+  }
+}
+
+#[cfg(unix)]
+impl From<&OsStr> for super::U8CString {
+  fn from(value: &OsStr) -> Self {
+    Self::from_slice(value.as_bytes())
+  }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+  use std::ffi::OsStr;
+  use std::os::unix::prelude::OsStrExt;
+
+  use crate::strings::{U8CString, WideCString};
+
+  #[test]
+  fn wide_round_trip() {
+    let os = OsStr::new("abc\u{1F600}");
+    let wide = WideCString::from(os);
+    assert_eq!(wide.as_slice(), &[b'a' as u32, b'b' as u32, b'c' as u32, 0x1F600]);
+    assert_eq!(wide.to_os_string(), os);
+  }
+  #[test]
+  fn wide_round_trip_invalid_utf8() {
+    let os = OsStr::from_bytes(b"a\xFF\xC3b");
+    let wide = WideCString::from(os);
+    assert_eq!(wide.as_slice(), &[b'a' as u32, 0xDCFF, 0xDCC3, b'b' as u32]);
+    assert_eq!(wide.to_os_string(), os);
+  }
+  #[test]
+  fn u8_as_os_str() {
+    let string = U8CString::from(OsStr::new("abc"));
+    assert_eq!(string.as_slice_with_nul(), b"abc\0");
+    assert_eq!(string.as_os_str(), OsStr::new("abc"));
+  }
+}
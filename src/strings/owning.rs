@@ -0,0 +1,91 @@
+use core::mem::ManuallyDrop;
+
+use super::CAllocator;
+
+/// Frees the memory of a C string owned by [`OwningCStr`]
+pub trait CDeleter<T> {
+  /// Frees the string pointed to by `ptr`
+  /// # Safety
+  /// `ptr` should point to a string owned by the caller that is not used afterwards
+  unsafe fn delete(self, ptr: *mut T);
+}
+
+/// Deleter calling `free()` of C runtime, i.e. for strings allocated by `malloc`
+/// NOTE: implements `CDeleter` only if `libc` feature is enabled
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LibcFree;
+
+#[cfg(feature = "libc")]
+impl<T> CDeleter<T> for LibcFree {
+  unsafe fn delete(self, ptr: *mut T) {
+    super::LibcAlloc.free(ptr.cast())
+  }
+}
+
+/// Deleter that does not free anything, i.e. for strings owned by C code that outlive the wrapper
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoopDeleter;
+
+impl<T> CDeleter<T> for NoopDeleter {
+  unsafe fn delete(self, _ptr: *mut T) {}
+}
+
+/// Deleter calling a rust closure
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FnDeleter<F>(pub F);
+
+impl<T, F: FnOnce(*mut T)> CDeleter<T> for FnDeleter<F> {
+  unsafe fn delete(self, ptr: *mut T) {
+    (self.0)(ptr)
+  }
+}
+
+/// Deleter calling an `extern "C"` function, e.g. a free function exported by a C library
+/// NOTE: the pointer is cast to the argument type of the function
+pub struct ExternCDeleter<P>(pub unsafe extern "C" fn(*mut P));
+
+impl<P> Clone for ExternCDeleter<P> {
+  fn clone(&self) -> Self {
+    *self
+  }
+}
+
+impl<P> Copy for ExternCDeleter<P> {}
+
+impl<P> core::fmt::Debug for ExternCDeleter<P> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.debug_tuple("ExternCDeleter").field(&(self.0 as *const ())).finish()
+  }
+}
+
+impl<T, P> CDeleter<T> for ExternCDeleter<P> {
+  unsafe fn delete(self, ptr: *mut T) {
+    (self.0)(ptr.cast())
+  }
+}
+
+/// Memory allocated by an allocator is freed by the same allocator
+impl<T, A: CAllocator> CDeleter<T> for A {
+  unsafe fn delete(self, ptr: *mut T) {
+    self.free(ptr.cast())
+  }
+}
+
+/// Owned nul-terminated string of `T` characters allocated outside of rust,
+/// that is freed by `D` on drop. See [`super::U8OwningCStr`], [`super::U16OwningCStr`] and [`super::U32OwningCStr`]
+pub struct OwningCStr<T, D: CDeleter<T> = LibcFree> {
+  pub(crate) data: *mut T,
+  pub(crate) deleter: ManuallyDrop<D>,
+}
+
+// SAFETY: the string is exclusively owned, so it can be moved or shared
+// as long as the characters and the deleter permit it
+unsafe impl<T: Send, D: CDeleter<T> + Send> Send for OwningCStr<T, D> {}
+unsafe impl<T: Sync, D: CDeleter<T> + Sync> Sync for OwningCStr<T, D> {}
+
+impl<T, D: CDeleter<T>> Drop for OwningCStr<T, D> {
+  fn drop(&mut self) {
+    let deleter = unsafe { ManuallyDrop::take(&mut self.deleter) };
+    unsafe { deleter.delete(self.data) }
+  }
+}
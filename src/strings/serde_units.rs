@@ -0,0 +1,140 @@
+//! Serde support for C strings.
+//!
+//! By default strings are (de)serialized as rust strings using the default encoding of the code unit
+//! (UTF-8 for u8, UTF-16 for u16 and UTF-32 for u32).
+//! This module can be used with `#[serde(with = "cutils::strings::serde_units")]`
+//! to (de)serialize them as arrays of raw code units (not including nul-terminator) instead.
+
+use serde::{de::Error as _, ser::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+use crate::prelude::*;
+
+use super::{
+  CStrCharType, StaticU16CStr, StaticU32CStr, StaticU8CStr, StrError, U16CStr, U16CString,
+  U32CStr, U32CString, U8CStr, U8CString,
+};
+
+/// Access to raw code units of string used by `serialize` and `deserialize`
+pub trait CodeUnits: Sized {
+  type Unit;
+  fn code_units(&self) -> &[Self::Unit];
+  fn from_code_units(units: Vec<Self::Unit>) -> Result<Self, StrError>;
+}
+
+pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+  T: CodeUnits,
+  T::Unit: Serialize,
+  S: Serializer,
+{
+  serializer.collect_seq(value.code_units())
+}
+
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+  T: CodeUnits,
+  T::Unit: Deserialize<'de>,
+  D: Deserializer<'de>,
+{
+  let units = Vec::<T::Unit>::deserialize(deserializer)?;
+  T::from_code_units(units).map_err(D::Error::custom)
+}
+
+macro_rules! serde_impls {
+  ($cstr:ident, $cstring:ident, $static:ident, $type:ty) => {
+    impl Serialize for $cstr {
+      fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let decoded = self
+          .decode()
+          .ok_or_else(|| S::Error::custom("string contains undecodable characters"))?;
+        serializer.serialize_str(&decoded)
+      }
+    }
+    impl Serialize for $cstring {
+      fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.as_ref().serialize(serializer)
+      }
+    }
+    impl<const CAPACITY: usize> Serialize for $static<CAPACITY> {
+      fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.as_ref().serialize(serializer)
+      }
+    }
+    impl<'de> Deserialize<'de> for $cstring {
+      fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let decoded = String::deserialize(deserializer)?;
+        <Self as CStrCharType>::encode(&decoded).map_err(D::Error::custom)
+      }
+    }
+    impl<'de, const CAPACITY: usize> Deserialize<'de> for $static<CAPACITY> {
+      fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let decoded = String::deserialize(deserializer)?;
+        <Self as CStrCharType>::encode(&decoded).map_err(D::Error::custom)
+      }
+    }
+    impl CodeUnits for $cstring {
+      type Unit = $type;
+      fn code_units(&self) -> &[$type] {
+        self.as_slice()
+      }
+      fn from_code_units(units: Vec<$type>) -> Result<Self, StrError> {
+        Ok(Self::new(units)?)
+      }
+    }
+    impl<const CAPACITY: usize> CodeUnits for $static<CAPACITY> {
+      type Unit = $type;
+      fn code_units(&self) -> &[$type] {
+        self.as_slice()
+      }
+      fn from_code_units(units: Vec<$type>) -> Result<Self, StrError> {
+        super::internals::check_no_nul(&units)?;
+        if units.len() > CAPACITY {
+          return Err(StrError::CapacityExceeded {
+            needed: units.len(),
+            capacity: CAPACITY,
+          });
+        }
+        Ok(Self::from_slice(&units))
+      }
+    }
+  };
+}
+
+serde_impls!(U8CStr, U8CString, StaticU8CStr, u8);
+serde_impls!(U16CStr, U16CString, StaticU16CStr, u16);
+serde_impls!(U32CStr, U32CString, StaticU32CStr, u32);
+
+#[cfg(test)]
+mod tests {
+  use serde::{Deserialize, Serialize};
+
+  use crate::strings::{StaticU16CStr, U16CString, U8CString};
+
+  #[derive(Debug, PartialEq, Serialize, Deserialize)]
+  struct Config {
+    name: U16CString,
+    path: StaticU16CStr<8>,
+    #[serde(with = "crate::strings::serde_units")]
+    raw: U8CString,
+  }
+
+  #[test]
+  fn round_trip() {
+    let config = Config {
+      name: U16CString::encode("dev\u{1F600}").unwrap(),
+      path: StaticU16CStr::encode("C:\\").unwrap(),
+      raw: U8CString::from_slice(b"ab"),
+    };
+    let json = serde_json::to_string(&config).unwrap();
+    assert_eq!(json, r#"{"name":"dev😀","path":"C:\\","raw":[97,98]}"#);
+    let parsed: Config = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed, config);
+  }
+
+  #[test]
+  fn capacity_errors() {
+    let err = serde_json::from_str::<StaticU16CStr<2>>(r#""abc""#).unwrap_err();
+    assert!(err.to_string().contains("capacity is 2"));
+    let err = serde_json::from_str::<U16CString>(r#""a\u0000b""#).unwrap_err();
+    assert!(err.to_string().contains("position 1"));
+  }
+}
@@ -1,35 +1,101 @@
+/// Formats the arguments into static str encoded as UTF-8
+/// NOTE: `const` form is evaluated at compile time and fails compilation
+/// if the literal doesn't fit into capacity or contains nul
+/// NOTE: the other form panics if the result doesn't fit into capacity or contains nul,
+/// consider using `try_static_u8cstr` to handle these errors
 #[macro_export]
 macro_rules! static_u8cstr {
+  (const $s:literal; $size:expr) => {{
+    const VALUE: $crate::strings::StaticU8CStr<{ $size }> =
+      $crate::strings::StaticU8CStr::<{ $size }>::from_str_const($s);
+    VALUE
+  }};
   ($s:literal; $size:expr $(,$args:expr)*) => {{
-    let mut tmp = $crate::strings::StaticU8CStr::<$size>::zeroed();
-    use std::io::Write;
-    drop(write!(tmp, $s $(, $args)*));
-    tmp
+    match $crate::try_static_u8cstr!($s; $size $(,$args)*) {
+      Ok(value) => value,
+      Err(_) => panic!("formatted string exceeds capacity of static str or contains nul character"),
+    }
   }};
 }
 
+/// Formats the arguments into static str encoded as UTF-16
+/// NOTE: `const` form is evaluated at compile time and fails compilation
+/// if the literal doesn't fit into capacity or contains nul
+/// NOTE: the other form panics if the result doesn't fit into capacity or contains nul,
+/// consider using `try_static_u16cstr` to handle these errors
 #[macro_export]
 macro_rules! static_u16cstr {
+  (const $s:literal; $size:expr) => {{
+    const VALUE: $crate::strings::StaticU16CStr<{ $size }> =
+      $crate::strings::StaticU16CStr::<{ $size }>::from_str_const($s);
+    VALUE
+  }};
   ($s:literal; $size:expr $(,$args:expr)*) => {{
-    let mut tmp = $crate::strings::StaticU16CStr::<$size>::zeroed();
-    use std::io::Write;
-    drop(write!(tmp, $s $(,$args)*));
-    tmp
+    match $crate::try_static_u16cstr!($s; $size $(,$args)*) {
+      Ok(value) => value,
+      Err(_) => panic!("formatted string exceeds capacity of static str or contains nul character"),
+    }
   }};
 }
 
+/// Formats the arguments into static str encoded as UTF-32
+/// NOTE: `const` form is evaluated at compile time and fails compilation
+/// if the literal doesn't fit into capacity or contains nul
+/// NOTE: the other form panics if the result doesn't fit into capacity or contains nul,
+/// consider using `try_static_u32cstr` to handle these errors
 #[macro_export]
 macro_rules! static_u32cstr {
+  (const $s:literal; $size:expr) => {{
+    const VALUE: $crate::strings::StaticU32CStr<{ $size }> =
+      $crate::strings::StaticU32CStr::<{ $size }>::from_str_const($s);
+    VALUE
+  }};
+  ($s:literal; $size:expr $(,$args:expr)*) => {{
+    match $crate::try_static_u32cstr!($s; $size $(,$args)*) {
+      Ok(value) => value,
+      Err(_) => panic!("formatted string exceeds capacity of static str or contains nul character"),
+    }
+  }};
+}
+
+/// Formats the arguments into static str
+/// NOTE: returns Err instead of truncating if result doesn't fit into capacity or contains nul
+#[macro_export]
+macro_rules! try_static_u8cstr {
+  ($s:literal; $size:expr $(,$args:expr)*) => {{
+    let mut tmp = $crate::strings::StaticU8CStr::<$size>::zeroed();
+    use core::fmt::Write;
+    write!(tmp, $s $(, $args)*).map(|_| tmp)
+  }};
+}
+
+/// Formats the arguments into static str
+/// NOTE: returns Err instead of truncating if result doesn't fit into capacity or contains nul
+#[macro_export]
+macro_rules! try_static_u16cstr {
+  ($s:literal; $size:expr $(,$args:expr)*) => {{
+    let mut tmp = $crate::strings::StaticU16CStr::<$size>::zeroed();
+    use core::fmt::Write;
+    write!(tmp, $s $(,$args)*).map(|_| tmp)
+  }};
+}
+
+/// Formats the arguments into static str
+/// NOTE: returns Err instead of truncating if result doesn't fit into capacity or contains nul
+#[macro_export]
+macro_rules! try_static_u32cstr {
   ($s:literal; $size:expr $(,$args:expr)*) => {{
     let mut tmp = $crate::strings::StaticU32CStr::<$size>::zeroed();
-    use std::io::Write;
-    drop(write!(tmp, $s $(,$args)*));
-    tmp
+    use core::fmt::Write;
+    write!(tmp, $s $(,$args)*).map(|_| tmp)
   }};
 }
 
 #[macro_export]
 macro_rules! static_cstr {
+  (const $s:literal; $size:expr) => {
+    $crate::static_u8cstr!(const $s; $size)
+  };
   ($s:literal; $size:expr $(,$args:expr)*) => {
     $crate::static_u8cstr!($s; $size $(,$args)*)  
   };
@@ -39,6 +105,9 @@ macro_rules! static_cstr {
 mod windows {
   #[macro_export]
   macro_rules! static_widecstr {
+    (const $s:literal; $size:expr) => {
+      $crate::static_u16cstr!(const $s; $size)
+    };
     ($s:literal; $size:expr $(,$args:expr)*) => {
       $crate::static_u16cstr!($s; $size $(,$args)*)
     };
@@ -49,6 +118,9 @@ mod windows {
 mod not_windows {
   #[macro_export]
   macro_rules! static_widecstr {
+    (const $s:literal; $size:expr) => {
+      $crate::static_u32cstr!(const $s; $size)
+    };
     ($s:literal; $size:expr $(,$args:expr)*) => {
       $crate::static_u32cstr!($s; $size $(,$args)*)
     };
@@ -1,5 +1,5 @@
 macro_rules! impl_macro {
-  ($name:ident, $type:ty, $item:ty, $fn:ident) => {
+  ($name:ident, $type:ident, $item:ty, $fn:ident) => {
     #[macro_export]
     macro_rules! $name {
       ($s:tt) => {{
@@ -25,10 +25,53 @@ macro_rules! impl_macro {
   };
 }
 
-impl_macro!(u8cstr, U8CStr, u8, length_as_u8_or_panic);
-impl_macro!(u16cstr, U16CStr, u16, length_as_u16_or_panic);
 impl_macro!(u32cstr, U32CStr, u32, length_as_u32_or_panic);
 
+/// Characters outside of BMP are encoded as UTF-16 surrogate pairs
+#[macro_export]
+macro_rules! u16cstr {
+  ($s:tt) => {{
+    const BYTES: &[u8] = $s.as_bytes();
+    const LEN: usize = unsafe { $crate::strings::internals::length_as_utf16(BYTES) };
+    const BUF: [u16; LEN + 1] = {
+      $crate::strings::internals::panic_on_invalid_utf8(BYTES);
+      let mut src = BYTES;
+      let mut buf = [0 as u16; LEN + 1];
+      let mut i = 0;
+      while let Some((ch, rest)) = unsafe { $crate::strings::internals::next_code_point(src) } {
+        src = rest;
+        let (units, count) = $crate::strings::internals::encode_utf16_units(ch);
+        buf[i] = units[0];
+        if count == 2 {
+          buf[i + 1] = units[1];
+        }
+        i += count;
+      }
+      buf
+    };
+    unsafe { $crate::strings::U16CStr::from_slice_unchecked(&BUF) }
+  }};
+}
+
+/// The literal is encoded as UTF-8, the same way as `StaticU8CStr::from_str_const` does
+#[macro_export]
+macro_rules! u8cstr {
+  ($s:tt) => {{
+    const BYTES: &[u8] = $s.as_bytes();
+    const LEN: usize = $crate::strings::internals::length_as_utf8(BYTES);
+    const BUF: [u8; LEN + 1] = {
+      let mut buf = [0u8; LEN + 1];
+      let mut i = 0;
+      while i < LEN {
+        buf[i] = BYTES[i];
+        i += 1;
+      }
+      buf
+    };
+    unsafe { $crate::strings::U8CStr::from_slice_unchecked(&BUF) }
+  }};
+}
+
 #[macro_export]
 macro_rules! cstr {
   ($s:tt) => {
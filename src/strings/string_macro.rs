@@ -5,7 +5,7 @@ macro_rules! u8cstring {
     tmp.to_owned()
   }};
   ($s:literal, $($args:expr),+) => {{
-    let mut tmp = $crate::strings::U8CString::new();
+    let mut tmp = $crate::strings::U8CString::default();
     use std::io::Write;
     drop(write!(tmp, $s, $($args),+));
     tmp
@@ -19,7 +19,7 @@ macro_rules! u16cstring {
     tmp.to_owned()
   }};
   ($s:literal, $($args:expr),+) => {{
-    let mut tmp = $crate::strings::U16CString::new();
+    let mut tmp = $crate::strings::U16CString::default();
     use std::io::Write;
     drop(write!(tmp, $s, $($args),+));
     tmp
@@ -33,7 +33,7 @@ macro_rules! u32cstring {
     tmp.to_owned()
   }};
   ($s:literal, $($args:expr),+) => {{
-    let mut tmp = $crate::strings::U32CString::new();
+    let mut tmp = $crate::strings::U32CString::default();
     use std::io::Write;
     drop(write!(tmp, $s, $($args),+));
     tmp
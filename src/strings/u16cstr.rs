@@ -1,48 +1,71 @@
 use super::{common::{
-  common_cstr_impls, common_cstring_impls, common_staticcstr_impls, common_staticstr_writes_impl,
-  common_str_writes_impl, common_string_writes_impl, common_owningcstr_impls
-}, internals::{decode_u16, encode_u16}};
-common_cstr_impls!(U16CStr, u16, U16CString, DisplayU16CStr, U16CStrIter, StaticU16CStr);
-common_staticcstr_impls!(StaticU16CStr, u16, U16CString, U16CStr, DisplayU16CStr, StaticU16CStrIntoIter, super::internals::encode_u16);
-common_cstring_impls!(U16CString, u16, U16CStr, DisplayU16CStr, U16CStringIter, super::internals::encode_u16);
+  common_cmp_impls, common_cstr_impls, common_debug_impl, common_staticcstr_impls, common_staticstr_writes_impl,
+  common_str_writes_impl, common_owningcstr_impls
+}, internals::{encode_into, encode_iter_u16, is_high_surrogate}, CStrCharType, StrError};
+#[cfg(feature = "alloc")]
+use super::{
+  common::{common_cstring_impls, common_string_writes_impl},
+  internals::{decode_u16, decode_u16_lossy},
+};
+#[cfg(feature = "alloc")]
+use crate::prelude::*;
+
+common_cstr_impls!(U16CStr, u16, U16CString, DisplayU16CStr, U16CStrIter, StaticU16CStr, super::internals::find_nul_u16, super::internals::find_nul_ptr_u16);
+common_staticcstr_impls!(StaticU16CStr, u16, U16CString, U16CStr, DisplayU16CStr, StaticU16CStrIntoIter, super::internals::encode_iter_u16, super::internals::encode_const_u16, super::internals::find_nul_u16);
+#[cfg(feature = "alloc")]
+common_cstring_impls!(U16CString, u16, U16CStr, DisplayU16CStr, U16CStringIter, U16CStringMut, super::internals::encode_u16, super::internals::find_nul_u16);
 common_owningcstr_impls!(U16OwningCStr, u16, U16CString, U16CStr, DisplayU16CStr, U16OwningCStrIter);
 
 common_str_writes_impl!(U16CStr, length_as_u16);
+#[cfg(feature = "alloc")]
 common_string_writes_impl!(U16CString, length_as_u16);
 common_staticstr_writes_impl!(StaticU16CStr<CAPACITY>, length_as_u16);
 
+#[cfg(feature = "alloc")]
 impl U16CStr {
+  /// Decodes UTF-16 contents until nul-terminator
+  /// NOTE: returns None if string contains unpaired surrogates
   pub fn decode(&self) -> Option<String> {
     decode_u16(self.as_slice())
   }
+  /// Decodes UTF-16 contents until nul-terminator replacing unpaired surrogates with U+FFFD
+  pub fn decode_lossy(&self) -> String {
+    decode_u16_lossy(self.as_slice())
+  }
 }
 
 impl<const CAP: usize> StaticU16CStr<CAP> {
-  pub fn encode(data: &str) -> Option<Self> {
-    let encoded = encode_u16(data)?;
-    if encoded.len() > CAP {
-      return None;
-    }
-    Some(Self::from_slice(&encoded))
+  /// Encodes `data` as UTF-16
+  /// NOTE: returns Err if `data` contains nul or does not fit into `CAP`
+  pub fn encode(data: &str) -> Result<Self, StrError> {
+    <Self as CStrCharType>::encode(data)
   }
-  pub fn encode_truncate(data: &str) -> Option<Self> {
-    let encoded = encode_u16(data)?;
-    let len = core::cmp::min(encoded.len(), CAP);
-    Some(Self::from_slice(&encoded[..len]))
+  /// Encodes as much of `data` as fits into `CAP` code units
+  /// NOTE: surrogate pair is never split at the truncation boundary
+  pub fn encode_truncate(data: &str) -> Result<Self, StrError> {
+    let mut result = Self::zeroed();
+    let needed = encode_into(encode_iter_u16(data), &mut result.0)?;
+    if needed > CAP && CAP > 0 && is_high_surrogate(result.0[CAP - 1]) {
+      result.0[CAP - 1] = 0;
+    }
+    Ok(result)
   }
 }
 
+#[cfg(feature = "alloc")]
 impl U16CString {
-  pub fn encode(data: &str) -> Option<Self> {
-    encode_u16(data).map(Into::into)
+  /// Encodes `data` as UTF-16
+  /// NOTE: returns Err if `data` contains nul
+  pub fn encode(data: &str) -> Result<Self, StrError> {
+    <Self as CStrCharType>::encode(data)
   }
 }
 
-#[cfg(not(feature = "no_std"))]
+#[cfg(feature = "std")]
 impl super::writes::io::Write16 for &mut U16CStr {
   fn write16(&mut self, buf: &[u16]) -> std::io::Result<usize> {
     let writable = self.capacity_usize();
-    let written = std::cmp::min(buf.len(), writable);
+    let written = core::cmp::min(buf.len(), writable);
     self.as_mut_slice_full()[0..written].copy_from_slice(&buf[0..written]);
     let new_self = &mut  self.0[written..];
 
@@ -55,21 +78,11 @@ impl super::writes::io::Write16 for &mut U16CStr {
   }
 }
 
-#[cfg(not(feature = "no_std"))]
+#[cfg(feature = "std")]
 impl super::writes::io::Write16 for U16CString {
   fn write16(&mut self, buf: &[u16]) -> std::io::Result<usize> {
-    let len = self.len_usize();
-    if buf.is_empty() {
-      return Ok(0);
-    }
-    self.0.truncate(len);
-    let len = self.0.write16(buf);
-    if !matches!(self.0.last(), Some(&0)) {
-      self.0.push(0);
-    }
-    let cap = self.0.capacity();
-    self.0.resize(cap, 0);
-    len
+    self.push_slice(buf);
+    Ok(buf.len())
   }
 
   fn flush16(&mut self) -> std::io::Result<()> {
@@ -89,13 +102,10 @@ impl super::writes::fmt::Write16 for &mut U16CStr {
   }
 }
 
+#[cfg(feature = "alloc")]
 impl super::writes::fmt::Write16 for U16CString {
   fn write16_str(&mut self, buf: &U16CStr) -> core::fmt::Result {
-    let len = self.len_usize();
-    self.0.resize(len + buf.len_with_nul_usize(), 0);
-    self.0[len..len + buf.len_with_nul_usize()].copy_from_slice(buf.as_slice_with_nul());
-    let cap = self.0.capacity();
-    self.0.resize(cap, 0);
+    self.push_str(buf);
     Ok(())
   }
 }
@@ -104,7 +114,8 @@ impl super::writes::fmt::Write16 for U16CString {
 mod tests {
   use crate::strings::io::Write16;
 
-  use super::{U16CStr, U16CString};
+  use super::{StaticU16CStr, U16CStr, U16CString};
+  use crate::strings::StrError;
 
   #[test]
   fn writes16_cstr() {
@@ -136,14 +147,14 @@ mod tests {
 
   #[test]
   fn writes16_cstring() {
-    let mut str = U16CString::new();
+    let mut str = U16CString::default();
     str.write16_all(&[1, 2, 3, 4]).unwrap();
     assert_eq!(str.as_slice_with_nul(), &[1, 2, 3, 4, 0]);
   }
 
   #[test]
   fn writes16_cstring_fmt() {
-    let mut str = U16CString::new();
+    let mut str = U16CString::default();
     use std::io::Write;
     str.write_fmt(format_args!("abc{}", 1)).unwrap();
     assert_eq!(
@@ -154,10 +165,179 @@ mod tests {
 
   #[test]
   fn writes16_cstring_twice() {
-    let mut str = U16CString::new();
+    let mut str = U16CString::default();
     str.write16(&[1, 2]).unwrap();
     assert_eq!(str.as_slice_with_nul(), &[1, 2, 0]);
     str.write16(&[3, 4]).unwrap();
     assert_eq!(str.as_slice_with_nul(), &[1, 2, 3, 4, 0]);
   }
+
+  #[test]
+  fn encodes_surrogate_pairs() {
+    let string = U16CString::encode("a\u{1F600}").unwrap();
+    assert_eq!(string.as_slice_with_nul(), &[b'a' as u16, 0xD83D, 0xDE00, 0]);
+    assert_eq!(string.decode().unwrap(), "a\u{1F600}");
+  }
+
+  #[test]
+  fn encode_truncate_keeps_pairs() {
+    let string = StaticU16CStr::<2>::encode_truncate("a\u{1F600}").unwrap();
+    assert_eq!(string.as_slice(), &[b'a' as u16]);
+    let string = StaticU16CStr::<3>::encode_truncate("a\u{1F600}").unwrap();
+    assert_eq!(string.as_slice(), &[b'a' as u16, 0xD83D, 0xDE00]);
+    assert_eq!(
+      StaticU16CStr::<2>::encode("a\u{1F600}"),
+      Err(StrError::CapacityExceeded { needed: 3, capacity: 2 })
+    );
+  }
+
+  #[test]
+  fn compares_with_str() {
+    let string = U16CString::encode("a\u{1F600}").unwrap();
+    assert_eq!(string, "a\u{1F600}");
+    assert_ne!(string, "a");
+    let static_str = StaticU16CStr::<4>::encode("a\u{1F600}").unwrap();
+    assert_eq!(static_str, string);
+  }
+  #[test]
+  fn debug_escapes() {
+    let buf = [b'a' as u16, b'"' as u16, b'\n' as u16, 0xD800, 0xD83D, 0xDE00, 0];
+    let string = U16CStr::try_from_slice(&buf).unwrap();
+    assert_eq!(format!("{:?}", string), "u16c\"a\\\"\\n\\u{d800}\u{1F600}\"");
+    let static_str = StaticU16CStr::<260>::encode("abc").unwrap();
+    assert_eq!(format!("{:?}", static_str), "u16c\"abc\"");
+    assert_eq!(
+      format!("{:#?}", static_str),
+      "StaticU16CStr {\n    value: u16c\"abc\",\n    len: 3,\n    capacity: 260,\n    width: 2,\n}"
+    );
+  }
+  #[test]
+  fn decodes_unpaired_surrogates() {
+    let buf = [b'a' as u16, 0xD800, b'b' as u16, 0];
+    let string = U16CStr::try_from_slice(&buf).unwrap();
+    assert_eq!(string.decode(), None);
+    assert_eq!(string.decode_lossy(), "a\u{FFFD}b");
+  }
+  #[test]
+  fn string_manipulation() {
+    let mut string = U16CString::encode("bc").unwrap();
+    string.push(b'd' as u16);
+    string.insert(0, b'a' as u16);
+    string.push_char('\u{1F600}').unwrap();
+    assert_eq!(string, "abcd\u{1F600}");
+    assert_eq!(string.remove(1), b'b' as u16);
+    string.retain(|ch| ch != b'c' as u16);
+    assert_eq!(string, "ad\u{1F600}");
+    string.push_str(&U16CString::encode(" a").unwrap());
+    assert_eq!(string.pop(), Some(b'a' as u16));
+    assert_eq!(string.len_usize(), 5);
+    string.clear();
+    assert_eq!(string.len_usize(), 0);
+  }
+  #[test]
+  fn static_try_push() {
+    let mut static_str = StaticU16CStr::<3>::encode("a").unwrap();
+    assert_eq!(static_str.try_push_char('\u{1F600}'), Ok(()));
+    assert_eq!(
+      static_str.try_push(b'b' as u16),
+      Err(StrError::CapacityExceeded { needed: 4, capacity: 3 })
+    );
+    assert_eq!(static_str, "a\u{1F600}");
+    assert_eq!(static_str.remove(0), b'a' as u16);
+    static_str.try_insert(0, b'x' as u16).unwrap();
+    assert_eq!(static_str, "x\u{1F600}");
+    static_str.retain(|ch| ch == b'x' as u16);
+    assert_eq!(static_str, "x");
+    assert_eq!(static_str.pop(), Some(b'x' as u16));
+    assert_eq!(static_str.pop(), None);
+  }
+  #[test]
+  fn queries() {
+    let string = U16CString::encode("  key=value ").unwrap();
+    let key = U16CString::encode("key").unwrap();
+    assert_eq!(string.find(key.as_slice()), Some(2));
+    assert!(string.trim_start().starts_with(key.as_slice()));
+    assert!(string.ends_with(&[b' ' as u16]));
+    assert_eq!(string.trim().len(), 9);
+    let parts: Vec<&[u16]> = string.trim_start().split(b'=' as u16).collect();
+    assert_eq!(parts.len(), 2);
+    assert_eq!(parts[0], key.as_slice());
+    let replaced = string.replace(key.as_slice(), &[b'k' as u16]);
+    assert_eq!(replaced, "  k=value ");
+  }
+  #[test]
+  fn case_conversions() {
+    let mut string = U16CString::encode("Déjà Vu").unwrap();
+    assert!(!string.eq_ignore_ascii_case(&U16CString::encode("dÉJÀ vU").unwrap()));
+    assert!(string.eq_ignore_ascii_case(&U16CString::encode("DéJà vU").unwrap()));
+    assert_eq!(string.to_ascii_lowercase(), "déjà vu");
+    assert_eq!(string.to_uppercase(), "DÉJÀ VU");
+    assert_eq!(U16CString::encode("STRASSE \u{10400}").unwrap().to_lowercase(), "strasse \u{10428}");
+    string.as_mut_str().make_ascii_uppercase();
+    assert_eq!(string, "DéJà VU");
+  }
+
+  #[test]
+  fn fmt_writes_surrogate_pairs() {
+    use core::fmt::Write;
+    let emoji = '\u{1F600}';
+    let mut string = U16CString::default();
+    write!(string, "a{}", emoji).unwrap();
+    write!(&mut string, "{}", 1).unwrap();
+    assert_eq!(string.as_slice_with_nul(), &[b'a' as u16, 0xD83D, 0xDE00, b'1' as u16, 0]);
+    assert!(string.write_str("b\0").is_err());
+
+    let mut static_str = StaticU16CStr::<4>::zeroed();
+    write!(static_str, "a\u{1F600}").unwrap();
+    assert_eq!(static_str.as_slice(), &[b'a' as u16, 0xD83D, 0xDE00]);
+    assert!(write!(static_str, "\u{1F600}").is_err());
+    write!(static_str, "b").unwrap();
+    assert_eq!(static_str.as_slice(), &[b'a' as u16, 0xD83D, 0xDE00, b'b' as u16]);
+
+    let mut buf = [0; 4];
+    let mut str = unsafe { U16CStr::from_mut_slice_unchecked(&mut buf) };
+    write!(str, "\u{1F600}").unwrap();
+    assert!(write!(str, "\u{1F600}").is_err());
+    assert_eq!(buf, [0xD83D, 0xDE00, 0, 0]);
+  }
+
+  #[test]
+  fn cstring_tracks_length() {
+    let mut string = U16CString::with_capacity(8);
+    assert!(string.capacity_usize() >= 8);
+    assert_eq!(string.as_slice_full(), &[0]);
+    string.push_slice(&[1, 2, 0, 3]);
+    assert_eq!(string.len_usize(), 2);
+    assert_eq!(string.as_slice_full(), &[1, 2, 0]);
+    string.reserve(100);
+    assert!(string.capacity_usize() >= 100);
+    assert_eq!(string.as_slice_with_nul(), &[1, 2, 0]);
+    assert_eq!(U16CString::from(vec![1, 0, 2]).as_slice_full(), &[1, 0]);
+  }
+
+  #[test]
+  fn cstring_filled_through_pointer() {
+    let mut string = U16CString::with_capacity(4);
+    unsafe {
+      core::ptr::copy_nonoverlapping([1, 2, 3, 0].as_ptr(), string.as_mut_ptr(), 4);
+      string.update_len();
+    }
+    assert_eq!(string.as_slice_with_nul(), &[1, 2, 3, 0]);
+    unsafe { string.set_len(1) };
+    assert_eq!(string.as_slice_with_nul(), &[1, 0]);
+  }
+
+  #[test]
+  fn cstring_filled_through_spare_capacity() {
+    let mut string = U16CString::with_capacity(4);
+    string.push(1);
+    let capacity = string.capacity_usize();
+    let spare = string.spare_capacity_mut();
+    assert_eq!(spare.len(), capacity - 1);
+    spare[0].write(2);
+    spare[1].write(3);
+    unsafe { string.set_len(string.len_usize() + 2) };
+    assert_eq!(string.as_slice_with_nul(), &[1, 2, 3, 0]);
+    assert_eq!(string.as_ref().as_slice(), &[1, 2, 3]);
+  }
 }
@@ -1,17 +1,27 @@
 use super::{
   common::{
-    common_cstr_impls, common_cstring_impls, common_staticcstr_impls, common_staticstr_writes_impl,
-    common_str_writes_impl, common_string_writes_impl, common_owningcstr_impls
+    common_cmp_impls, common_cstr_impls, common_debug_impl, common_staticcstr_impls, common_staticstr_writes_impl,
+    common_str_writes_impl, common_owningcstr_impls
   },
-  internals::{decode_u32, encode_u32},
+  internals::{encode_into, encode_iter_u32},
+  CStrCharType, StrError,
 };
+#[cfg(feature = "alloc")]
+use super::{
+  common::{common_cstring_impls, common_string_writes_impl},
+  internals::decode_u32,
+};
+#[cfg(feature = "alloc")]
+use crate::prelude::*;
 common_cstr_impls!(
   U32CStr,
   u32,
   U32CString,
   DisplayU32CStr,
   U32CStrIter,
-  StaticU32CStr
+  StaticU32CStr,
+  super::internals::find_nul_u32,
+  super::internals::find_nul_ptr_u32
 );
 common_staticcstr_impls!(
   StaticU32CStr,
@@ -20,15 +30,20 @@ common_staticcstr_impls!(
   U32CStr,
   DisplayU32CStr,
   StaticU32CStrIntoIter,
-  super::internals::encode_u32
+  super::internals::encode_iter_u32,
+  super::internals::encode_const_u32,
+  super::internals::find_nul_u32
 );
-common_cstring_impls!(U32CString, u32, U32CStr, DisplayU32CStr, U32CStringIter, super::internals::encode_u32);
+#[cfg(feature = "alloc")]
+common_cstring_impls!(U32CString, u32, U32CStr, DisplayU32CStr, U32CStringIter, U32CStringMut, super::internals::encode_u32, super::internals::find_nul_u32);
 common_owningcstr_impls!(U32OwningCStr, u32, U32CString, U32CStr, DisplayU32CStr, U32OwningCStrIter);
 
 common_str_writes_impl!(U32CStr, length_as_u32);
+#[cfg(feature = "alloc")]
 common_string_writes_impl!(U32CString, length_as_u32);
 common_staticstr_writes_impl!(StaticU32CStr<CAPACITY>, length_as_u32);
 
+#[cfg(feature = "alloc")]
 impl U32CStr {
   pub fn decode(&self) -> Option<String> {
     decode_u32(self.as_slice())
@@ -36,31 +51,33 @@ impl U32CStr {
 }
 
 impl<const CAP: usize> StaticU32CStr<CAP> {
-  pub fn encode(data: &str) -> Option<Self> {
-    let encoded = encode_u32(data)?;
-    if encoded.len() > CAP {
-      return None;
-    }
-    Some(Self::from_slice(&encoded))
+  /// Encodes `data` as UTF-32
+  /// NOTE: returns Err if `data` contains nul or does not fit into `CAP`
+  pub fn encode(data: &str) -> Result<Self, StrError> {
+    <Self as CStrCharType>::encode(data)
   }
   pub fn encode_truncate(data: &str) -> Self {
-    let encoded = encode_u32(data).unwrap();
-    let len = core::cmp::min(encoded.len(), CAP);
-    Self::from_slice(&encoded[..len])
+    let mut result = Self::zeroed();
+    // NOTE: every char is representable in UTF-32
+    let _ = encode_into(encode_iter_u32(data), &mut result.0);
+    result
   }
 }
 
+#[cfg(feature = "alloc")]
 impl U32CString {
-  pub fn encode(data: &str) -> Self {
-    encode_u32(data).unwrap().into()
+  /// Encodes `data` as UTF-32
+  /// NOTE: returns Err if `data` contains nul
+  pub fn encode(data: &str) -> Result<Self, StrError> {
+    <Self as CStrCharType>::encode(data)
   }
 }
 
-#[cfg(not(feature = "no_std"))]
+#[cfg(feature = "std")]
 impl super::writes::io::Write32 for &mut U32CStr {
   fn write32(&mut self, buf: &[u32]) -> std::io::Result<usize> {
     let writable = self.capacity_usize();
-    let written = std::cmp::min(buf.len(), writable);
+    let written = core::cmp::min(buf.len(), writable);
     self.as_mut_slice_full()[0..written].copy_from_slice(&buf[0..written]);
     let new_self = &mut self.0[written..];
 
@@ -73,21 +90,11 @@ impl super::writes::io::Write32 for &mut U32CStr {
   }
 }
 
-#[cfg(not(feature = "no_std"))]
+#[cfg(feature = "std")]
 impl super::writes::io::Write32 for U32CString {
   fn write32(&mut self, buf: &[u32]) -> std::io::Result<usize> {
-    let len = self.len_usize();
-    if buf.is_empty() {
-      return Ok(0);
-    }
-    self.0.truncate(len);
-    let len = self.0.write32(buf);
-    if !matches!(self.0.last(), Some(&0)) {
-      self.0.push(0);
-    }
-    let cap = self.0.capacity();
-    self.0.resize(cap, 0);
-    len
+    self.push_slice(buf);
+    Ok(buf.len())
   }
 
   fn flush32(&mut self) -> std::io::Result<()> {
@@ -107,13 +114,10 @@ impl super::writes::fmt::Write32 for &mut U32CStr {
   }
 }
 
+#[cfg(feature = "alloc")]
 impl super::writes::fmt::Write32 for U32CString {
   fn write32_str(&mut self, buf: &U32CStr) -> core::fmt::Result {
-    let len = self.len_usize();
-    self.0.resize(len + buf.len_with_nul_usize(), 0);
-    self.0[len..len + buf.len_with_nul_usize()].copy_from_slice(buf.as_slice_with_nul());
-    let cap = self.0.capacity();
-    self.0.resize(cap, 0);
+    self.push_str(buf);
     Ok(())
   }
 }
@@ -154,14 +158,14 @@ mod tests {
 
   #[test]
   fn writes32_cstring() {
-    let mut str = U32CString::new();
+    let mut str = U32CString::default();
     str.write32_all(&[1, 2, 3, 4]).unwrap();
     assert_eq!(str.as_slice_with_nul(), &[1, 2, 3, 4, 0]);
   }
 
   #[test]
   fn writes32_cstring_fmt() {
-    let mut str = U32CString::new();
+    let mut str = U32CString::default();
     use std::io::Write;
     str.write_fmt(format_args!("abc{}", 1)).unwrap();
     assert_eq!(
@@ -172,10 +176,41 @@ mod tests {
 
   #[test]
   fn writes32_cstring_twice() {
-    let mut str = U32CString::new();
+    let mut str = U32CString::default();
     str.write32(&[1, 2]).unwrap();
     assert_eq!(str.as_slice_with_nul(), &[1, 2, 0]);
     str.write32(&[3, 4]).unwrap();
     assert_eq!(str.as_slice_with_nul(), &[1, 2, 3, 4, 0]);
   }
+
+  #[test]
+  fn debug_escapes_invalid() {
+    let buf = [b'a' as u32, 0x110000, 0x1F600, 0];
+    let string = U32CStr::try_from_slice(&buf).unwrap();
+    assert_eq!(format!("{:?}", string), "u32c\"a\\u{110000}\u{1F600}\"");
+  }
+
+  #[test]
+  fn fmt_writes_scalar_values() {
+    use core::fmt::Write;
+    let mut string = U32CString::default();
+    write!(string, "a\u{1F600}{}", 1).unwrap();
+    assert_eq!(string.as_slice_with_nul(), &[b'a' as u32, 0x1F600, b'1' as u32, 0]);
+    let mut static_str = crate::strings::StaticU32CStr::<2>::zeroed();
+    write!(static_str, "\u{1F600}\u{1F600}").unwrap();
+    assert_eq!(static_str.as_slice(), &[0x1F600, 0x1F600]);
+    assert!(write!(static_str, "a").is_err());
+  }
+
+  #[test]
+  fn from_str_const_matches_encode() {
+    use crate::strings::{StaticU32CStr, StrError};
+    const STRING: StaticU32CStr<3> = StaticU32CStr::<3>::from_str_const("a\u{1F600}");
+    assert_eq!(STRING.as_slice(), &[b'a' as u32, 0x1F600]);
+    assert_eq!(
+      StaticU32CStr::<1>::try_from_str_const("ab").err(),
+      Some(StrError::CapacityExceeded { needed: 2, capacity: 1 })
+    );
+    assert_eq!(StaticU32CStr::<3>::try_from_str_const("a\0").err(), Some(StrError::InteriorNul { pos: 1 }));
+  }
 }
@@ -12,12 +12,12 @@ macro_rules! common_conv_cstr_impl {
     }
     impl From<&$dst> for &$src {
       fn from(value: &$dst) -> Self {
-        unsafe { std::mem::transmute(value.as_slice()) }
+        unsafe { core::mem::transmute(value.as_slice()) }
       }
     }
     impl From<&mut $dst> for &mut $src {
       fn from(value: &mut $dst) -> Self {
-        unsafe { std::mem::transmute(value.as_mut_slice()) }
+        unsafe { core::mem::transmute(value.as_mut_slice()) }
       }
     }
   };
@@ -27,6 +27,7 @@ common_conv_cstr_impl!(super::U32CStr, widestring::U32CStr);
 macro_rules! common_conv_cstring_impl {
   ($src:ty, $dst:ty) => {
     impl From<$src> for $dst {
+      /// NOTE: contents after the first nul character are dropped
       fn from(value: $src) -> Self {
         Self::from_vec_truncate(value)
       }
@@ -0,0 +1,380 @@
+use super::io::{Read16, Read32, Write16, Write32};
+
+/// Byte order of encoded code units
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteOrder {
+  LittleEndian,
+  BigEndian,
+}
+
+impl ByteOrder {
+  /// Byte order of the target platform
+  #[cfg(target_endian = "little")]
+  pub const NATIVE: ByteOrder = ByteOrder::LittleEndian;
+  /// Byte order of the target platform
+  #[cfg(target_endian = "big")]
+  pub const NATIVE: ByteOrder = ByteOrder::BigEndian;
+}
+
+// Number of code units converted to bytes at once
+const ENCODE_CHUNK: usize = 256;
+
+macro_rules! encoder_impl {
+  ($name:ident, $trait:ident, $type:ty, $write:ident, $flush:ident, $doc:literal) => {
+    #[doc = $doc]
+    /// NOTE: code units are written as is, without checking that they form valid text
+    #[derive(Debug)]
+    pub struct $name<W: std::io::Write> {
+      inner: W,
+      order: ByteOrder,
+    }
+
+    impl<W: std::io::Write> $name<W> {
+      pub fn new(inner: W, order: ByteOrder) -> Self {
+        Self { inner, order }
+      }
+      pub fn get_ref(&self) -> &W {
+        &self.inner
+      }
+      pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+      }
+      pub fn into_inner(self) -> W {
+        self.inner
+      }
+    }
+
+    impl<W: std::io::Write> $trait for $name<W> {
+      /// Writes all of `buf` to the underlying writer
+      /// NOTE: this method returns either Err or the length of `buf`,
+      /// so that a code unit is never split between writes
+      fn $write(&mut self, buf: &[$type]) -> std::io::Result<usize> {
+        const SIZE: usize = core::mem::size_of::<$type>();
+        let mut bytes = [0u8; ENCODE_CHUNK * SIZE];
+        for chunk in buf.chunks(ENCODE_CHUNK) {
+          for (unit, dst) in chunk.iter().zip(bytes.chunks_exact_mut(SIZE)) {
+            dst.copy_from_slice(&match self.order {
+              ByteOrder::LittleEndian => unit.to_le_bytes(),
+              ByteOrder::BigEndian => unit.to_be_bytes(),
+            });
+          }
+          self.inner.write_all(&bytes[..chunk.len() * SIZE])?;
+        }
+        Ok(buf.len())
+      }
+
+      fn $flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+      }
+    }
+  };
+}
+
+encoder_impl!(
+  Utf16Encoder,
+  Write16,
+  u16,
+  write16,
+  flush16,
+  "Adapter writing UTF-16 code units as bytes of the given byte order to any byte sink"
+);
+encoder_impl!(
+  Utf32Encoder,
+  Write32,
+  u32,
+  write32,
+  flush32,
+  "Adapter writing UTF-32 code units as bytes of the given byte order to any byte sink"
+);
+
+macro_rules! reader_impl {
+  ($name:ident, $trait:ident, $type:ty, $read:ident, $doc:literal) => {
+    #[doc = $doc]
+    #[derive(Debug)]
+    pub struct $name<R: std::io::Read> {
+      inner: R,
+      order: ByteOrder,
+      // Bytes of a code unit split between reads from the underlying reader
+      pending: [u8; core::mem::size_of::<$type>()],
+      pending_len: usize,
+    }
+
+    impl<R: std::io::Read> $name<R> {
+      pub fn new(inner: R, order: ByteOrder) -> Self {
+        Self { inner, order, pending: [0; core::mem::size_of::<$type>()], pending_len: 0 }
+      }
+      pub fn get_ref(&self) -> &R {
+        &self.inner
+      }
+      pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+      }
+      pub fn into_inner(self) -> R {
+        self.inner
+      }
+    }
+
+    impl<R: std::io::Read> $trait for $name<R> {
+      /// NOTE: end of the stream in the middle of a code unit results in `UnexpectedEof` error
+      fn $read(&mut self, buf: &mut [$type]) -> std::io::Result<usize> {
+        const SIZE: usize = core::mem::size_of::<$type>();
+        let wanted = core::cmp::min(buf.len(), ENCODE_CHUNK) * SIZE;
+        if wanted == 0 {
+          return Ok(0);
+        }
+        let mut bytes = [0u8; ENCODE_CHUNK * SIZE];
+        let mut filled = self.pending_len;
+        bytes[..filled].copy_from_slice(&self.pending[..filled]);
+        while filled < SIZE {
+          // Incomplete unit is kept, so that it is not lost on error
+          self.pending[..filled].copy_from_slice(&bytes[..filled]);
+          self.pending_len = filled;
+          match self.inner.read(&mut bytes[filled..wanted])? {
+            0 if filled == 0 => return Ok(0),
+            0 => {
+              return Err(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                "stream ended in the middle of a code unit",
+              ))
+            }
+            read => filled += read,
+          }
+        }
+        let units = filled / SIZE;
+        for (unit, src) in buf.iter_mut().zip(bytes[..units * SIZE].chunks_exact(SIZE)) {
+          let src = src.try_into().unwrap();
+          *unit = match self.order {
+            ByteOrder::LittleEndian => <$type>::from_le_bytes(src),
+            ByteOrder::BigEndian => <$type>::from_be_bytes(src),
+          };
+        }
+        self.pending_len = filled - units * SIZE;
+        self.pending[..self.pending_len].copy_from_slice(&bytes[units * SIZE..filled]);
+        Ok(units)
+      }
+    }
+  };
+}
+
+reader_impl!(
+  Utf16Reader,
+  Read16,
+  u16,
+  read16,
+  "Adapter reading UTF-16 code units of the given byte order from any byte source"
+);
+reader_impl!(
+  Utf32Reader,
+  Read32,
+  u32,
+  read32,
+  "Adapter reading UTF-32 code units of the given byte order from any byte source"
+);
+
+fn fmt_error(_: core::fmt::Error) -> std::io::Error {
+  std::io::Error::other("formatter returned an error")
+}
+
+/// Adapter decoding UTF-16 code units into text written to `core::fmt::Write`
+/// NOTE: surrogate pairs split between writes are joined,
+/// unpaired surrogates are replaced with U+FFFD
+#[derive(Debug)]
+pub struct Utf16Decoder<W: core::fmt::Write> {
+  inner: W,
+  // High surrogate at the end of the previous write
+  pending: Option<u16>,
+}
+
+impl<W: core::fmt::Write> Utf16Decoder<W> {
+  pub fn new(inner: W) -> Self {
+    Self { inner, pending: None }
+  }
+  pub fn get_ref(&self) -> &W {
+    &self.inner
+  }
+  pub fn get_mut(&mut self) -> &mut W {
+    &mut self.inner
+  }
+  /// Returns the writer
+  /// NOTE: a high surrogate left at the end of the input is dropped, use `finish` to write U+FFFD for it
+  pub fn into_inner(self) -> W {
+    self.inner
+  }
+  /// Writes U+FFFD for a high surrogate left at the end of the input and returns the writer
+  pub fn finish(mut self) -> Result<W, core::fmt::Error> {
+    if self.pending.take().is_some() {
+      self.inner.write_char(char::REPLACEMENT_CHARACTER)?;
+    }
+    Ok(self.inner)
+  }
+}
+
+impl<W: core::fmt::Write> Write16 for Utf16Decoder<W> {
+  fn write16(&mut self, buf: &[u16]) -> std::io::Result<usize> {
+    let mut units = buf;
+    if let Some(high) = self.pending.take() {
+      let Some((&low, rest)) = units.split_first() else {
+        self.pending = Some(high);
+        return Ok(0);
+      };
+      match char::decode_utf16([high, low]).next() {
+        Some(Ok(ch)) => {
+          self.inner.write_char(ch).map_err(fmt_error)?;
+          units = rest;
+        }
+        // The unit is not a low surrogate, so it is decoded on its own
+        _ => self.inner.write_char(char::REPLACEMENT_CHARACTER).map_err(fmt_error)?,
+      }
+    }
+    if let [rest @ .., last] = units {
+      if super::super::internals::is_high_surrogate(*last) {
+        self.pending = Some(*last);
+        units = rest;
+      }
+    }
+    for ch in char::decode_utf16(units.iter().copied()) {
+      self.inner.write_char(ch.unwrap_or(char::REPLACEMENT_CHARACTER)).map_err(fmt_error)?;
+    }
+    Ok(buf.len())
+  }
+
+  fn flush16(&mut self) -> std::io::Result<()> {
+    Ok(())
+  }
+}
+
+/// Adapter decoding UTF-32 code units into text written to `core::fmt::Write`
+/// NOTE: units that are not valid code points are replaced with U+FFFD
+#[derive(Debug)]
+pub struct Utf32Decoder<W: core::fmt::Write> {
+  inner: W,
+}
+
+impl<W: core::fmt::Write> Utf32Decoder<W> {
+  pub fn new(inner: W) -> Self {
+    Self { inner }
+  }
+  pub fn get_ref(&self) -> &W {
+    &self.inner
+  }
+  pub fn get_mut(&mut self) -> &mut W {
+    &mut self.inner
+  }
+  pub fn into_inner(self) -> W {
+    self.inner
+  }
+  /// Returns the writer, same as `into_inner` since no input is buffered
+  pub fn finish(self) -> Result<W, core::fmt::Error> {
+    Ok(self.inner)
+  }
+}
+
+impl<W: core::fmt::Write> Write32 for Utf32Decoder<W> {
+  fn write32(&mut self, buf: &[u32]) -> std::io::Result<usize> {
+    for unit in buf {
+      let ch = char::from_u32(*unit).unwrap_or(char::REPLACEMENT_CHARACTER);
+      self.inner.write_char(ch).map_err(fmt_error)?;
+    }
+    Ok(buf.len())
+  }
+
+  fn flush32(&mut self) -> std::io::Result<()> {
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{ByteOrder, Utf16Decoder, Utf16Encoder, Utf16Reader, Utf32Decoder, Utf32Encoder, Utf32Reader};
+  use crate::strings::io::{BufRead16, BufRead32, BufReader16, BufReader32, Read16, Read32, Write16, Write32};
+  use crate::strings::{U16CString, U32CString};
+
+  #[test]
+  fn encodes_bytes() {
+    let string = U16CString::encode("a\u{1F600}").unwrap();
+    let mut encoder = Utf16Encoder::new(Vec::new(), ByteOrder::LittleEndian);
+    encoder.write16_all(string.as_slice()).unwrap();
+    assert_eq!(encoder.into_inner(), [b'a', 0, 0x3D, 0xD8, 0x00, 0xDE]);
+    let mut encoder = Utf16Encoder::new(Vec::new(), ByteOrder::BigEndian);
+    encoder.write16_all(string.as_slice()).unwrap();
+    assert_eq!(encoder.into_inner(), [0, b'a', 0xD8, 0x3D, 0xDE, 0x00]);
+
+    let string = U32CString::encode("\u{1F600}").unwrap();
+    let mut encoder = Utf32Encoder::new(Vec::new(), ByteOrder::BigEndian);
+    encoder.write32_all(string.as_slice()).unwrap();
+    assert_eq!(encoder.into_inner(), [0, 0x01, 0xF6, 0x00]);
+  }
+
+  #[test]
+  fn decodes_surrogates_across_writes() {
+    let mut decoder = Utf16Decoder::new(String::new());
+    decoder.write16_all(&[b'a' as u16, 0xD83D]).unwrap();
+    assert_eq!(decoder.get_ref(), "a");
+    decoder.write16_all(&[0xDE00, 0xDE00, 0xD83D]).unwrap();
+    decoder.write16_all(&[b'b' as u16, 0xD83D]).unwrap();
+    assert_eq!(decoder.finish().unwrap(), "a\u{1F600}\u{FFFD}\u{FFFD}b\u{FFFD}");
+
+    let mut decoder = Utf16Decoder::new(String::new());
+    decoder.write16_all(&[b'a' as u16, 0xD83D]).unwrap();
+    decoder.get_mut().push('b');
+    assert_eq!(decoder.into_inner(), "ab");
+
+    let mut decoder = Utf32Decoder::new(String::new());
+    decoder.write32_all(&[b'a' as u32, 0x1F600, 0x110000]).unwrap();
+    decoder.get_mut().push('b');
+    assert_eq!(decoder.finish().unwrap(), "a\u{1F600}\u{FFFD}b");
+  }
+
+  #[test]
+  fn reads_units() {
+    // Reader returning a single byte at a time splits every code unit
+    struct Bytewise<'a>(&'a [u8]);
+    impl std::io::Read for Bytewise<'_> {
+      fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = core::cmp::min(1, core::cmp::min(buf.len(), self.0.len()));
+        buf[..read].copy_from_slice(&self.0[..read]);
+        self.0 = &self.0[read..];
+        Ok(read)
+      }
+    }
+    let mut reader = Utf16Reader::new(Bytewise(&[0, b'a', 0xD8, 0x3D, 0xDE, 0x00]), ByteOrder::BigEndian);
+    let mut units = [0u16; 3];
+    reader.read_exact16(&mut units).unwrap();
+    assert_eq!(units, [b'a' as u16, 0xD83D, 0xDE00]);
+    assert_eq!(reader.read16(&mut units).unwrap(), 0);
+
+    let mut reader = Utf32Reader::new(&[0x00, 0xF6, 0x01, 0x00, b'a'][..], ByteOrder::LittleEndian);
+    let mut units = [0u32; 2];
+    assert_eq!(reader.read32(&mut units).unwrap(), 1);
+    assert_eq!(units[0], 0x1F600);
+    let err = reader.read32(&mut units).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+  }
+
+  #[test]
+  fn reads_strings_until_nul() {
+    let bytes: Vec<u8> = "ab\0\0c".encode_utf16().flat_map(u16::to_le_bytes).collect();
+    let mut reader = BufReader16::with_capacity(2, Utf16Reader::new(&bytes[..], ByteOrder::LittleEndian));
+    assert_eq!(reader.read_until_nul().unwrap().unwrap(), U16CString::encode("ab").unwrap());
+    assert_eq!(reader.read_until_nul().unwrap().unwrap(), U16CString::encode("").unwrap());
+    assert_eq!(reader.read_until_nul().unwrap().unwrap(), U16CString::encode("c").unwrap());
+    assert!(reader.read_until_nul().unwrap().is_none());
+
+    let bytes: Vec<u8> = [b'a' as u32, 0, 0x1F600, b'b' as u32].iter().flat_map(|u| u.to_be_bytes()).collect();
+    let mut reader = BufReader32::with_capacity(1, Utf32Reader::new(&bytes[..], ByteOrder::BigEndian));
+    assert_eq!(reader.read_until_nul().unwrap().unwrap(), U32CString::encode("a").unwrap());
+    assert_eq!(reader.read_until_nul().unwrap().unwrap(), U32CString::encode("\u{1F600}b").unwrap());
+    assert!(reader.read_until_nul().unwrap().is_none());
+    let mut units: &[u32] = &[b'a' as u32, 0];
+    assert_eq!(units.read_until_nul().unwrap().unwrap(), U32CString::encode("a").unwrap());
+    assert!(units.read_until_nul().unwrap().is_none());
+
+    let string = U16CString::encode("abc").unwrap();
+    let mut cstr = string.as_ref();
+    let mut units = [0u16; 2];
+    cstr.read_exact16(&mut units).unwrap();
+    assert_eq!(cstr.as_slice_with_nul(), [b'c' as u16, 0]);
+    assert_eq!(cstr.read16(&mut units).unwrap(), 1);
+    assert_eq!(cstr.read16(&mut units).unwrap(), 0);
+  }
+}
@@ -0,0 +1,228 @@
+use crate::strings::internals::EncodeChar;
+
+/// C strings that characters can be appended to by [`CStrWriter`]
+pub trait CStrSink {
+  type Char: EncodeChar;
+  /// Appends all of `units` or nothing in case of they do not fit,
+  /// returns whether the units were appended
+  fn push_units(&mut self, units: &[Self::Char]) -> bool;
+}
+
+/// Action taken by [`CStrWriter`] on input it can not store as is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fallback {
+  /// Writes the given character instead
+  /// NOTE: the replacement is dropped in case of it is unrepresentable as well
+  Replace(char),
+  /// Drops the input
+  Skip,
+  /// Fails the write with `ErrorKind::InvalidData`
+  Error,
+}
+
+/// Adapter implementing `std::io::Write` for C strings that decodes UTF-8 input and encodes it
+/// using the default encoding of the code unit (UTF-8 for u8, UTF-16 for u16 and UTF-32 for u32)
+/// NOTE: by default unrepresentable characters (including nul) and invalid UTF-8 fail the write,
+/// use `on_unrepresentable` and `on_invalid_utf8` to replace or skip them instead
+/// NOTE: in case of a fixed size string is full the write stops at the last character that fits
+pub struct CStrWriter<'a, S: CStrSink + ?Sized> {
+  sink: &'a mut S,
+  on_unrepresentable: Fallback,
+  on_invalid_utf8: Fallback,
+  // Beginning of a UTF-8 sequence split between writes
+  pending: [u8; 4],
+  pending_len: usize,
+}
+
+impl<'a, S: CStrSink + ?Sized> CStrWriter<'a, S> {
+  pub fn new(sink: &'a mut S) -> Self {
+    Self {
+      sink,
+      on_unrepresentable: Fallback::Error,
+      on_invalid_utf8: Fallback::Error,
+      pending: [0; 4],
+      pending_len: 0,
+    }
+  }
+  /// Sets the action on characters that are not representable in the encoding of the string
+  pub fn on_unrepresentable(mut self, fallback: Fallback) -> Self {
+    self.on_unrepresentable = fallback;
+    self
+  }
+  /// Sets the action on byte sequences that are not valid UTF-8
+  pub fn on_invalid_utf8(mut self, fallback: Fallback) -> Self {
+    self.on_invalid_utf8 = fallback;
+    self
+  }
+  /// Handles an incomplete UTF-8 sequence left at the end of the input and returns the string
+  pub fn finish(mut self) -> std::io::Result<&'a mut S> {
+    if self.pending_len != 0 {
+      self.pending_len = 0;
+      self.fallback(self.on_invalid_utf8, "incomplete UTF-8 sequence at the end of input")?;
+    }
+    Ok(self.sink)
+  }
+
+  fn push_char(&mut self, ch: char) -> Option<bool> {
+    let mut units = [S::Char::default(); 4];
+    match S::Char::encode_char(ch, &mut units) {
+      Some(len) if ch != '\0' => Some(self.sink.push_units(&units[..len])),
+      _ => None,
+    }
+  }
+  // Returns false in case of there is no space left for the character
+  fn write_char(&mut self, ch: char) -> std::io::Result<bool> {
+    match self.push_char(ch) {
+      Some(stored) => Ok(stored),
+      None => self.fallback(self.on_unrepresentable, "character is unrepresentable in target encoding"),
+    }
+  }
+  fn fallback(&mut self, fallback: Fallback, message: &'static str) -> std::io::Result<bool> {
+    match fallback {
+      Fallback::Replace(ch) => Ok(self.push_char(ch).unwrap_or(true)),
+      Fallback::Skip => Ok(true),
+      Fallback::Error => Err(std::io::Error::new(std::io::ErrorKind::InvalidData, message)),
+    }
+  }
+}
+
+impl<S: CStrSink + ?Sized> CStrWriter<'_, S> {
+  // Stores characters from `buf` counting consumed bytes until the input ends, the string is full or an error occurs
+  fn write_counted(&mut self, buf: &[u8], consumed: &mut usize) -> std::io::Result<()> {
+    while self.pending_len != 0 && *consumed < buf.len() {
+      self.pending[self.pending_len] = buf[*consumed];
+      match core::str::from_utf8(&self.pending[..self.pending_len + 1]) {
+        Ok(valid) => {
+          if !self.write_char(valid.chars().next().unwrap_or_default())? {
+            return Ok(());
+          }
+          self.pending_len = 0;
+        }
+        Err(err) if err.error_len().is_none() => self.pending_len += 1,
+        // The byte does not continue the pending sequence, so it is processed on its own
+        Err(_) => {
+          if !self.fallback(self.on_invalid_utf8, "stream did not contain valid UTF-8")? {
+            return Ok(());
+          }
+          self.pending_len = 0;
+          continue;
+        }
+      }
+      *consumed += 1;
+    }
+    while *consumed < buf.len() {
+      let rest = &buf[*consumed..];
+      let (valid, error) = match core::str::from_utf8(rest) {
+        Ok(valid) => (valid, None),
+        Err(err) => {
+          let valid = unsafe { core::str::from_utf8_unchecked(&rest[..err.valid_up_to()]) };
+          (valid, Some(err.error_len()))
+        }
+      };
+      for ch in valid.chars() {
+        if !self.write_char(ch)? {
+          return Ok(());
+        }
+        *consumed += ch.len_utf8();
+      }
+      match error {
+        None => {}
+        // Incomplete sequence at the end of the buffer, it is completed by the next write
+        Some(None) => {
+          let tail = &buf[*consumed..];
+          self.pending[..tail.len()].copy_from_slice(tail);
+          self.pending_len = tail.len();
+          *consumed = buf.len();
+        }
+        Some(Some(len)) => {
+          if !self.fallback(self.on_invalid_utf8, "stream did not contain valid UTF-8")? {
+            return Ok(());
+          }
+          *consumed += len;
+        }
+      }
+    }
+    Ok(())
+  }
+}
+
+impl<S: CStrSink + ?Sized> std::io::Write for CStrWriter<'_, S> {
+  /// NOTE: in case of an error after some input was stored, the stored part is reported as written
+  /// and the error is returned by the next call, which starts at the offending input
+  fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+    let mut consumed = 0;
+    match self.write_counted(buf, &mut consumed) {
+      Err(_) if consumed > 0 => Ok(consumed),
+      result => result.map(|()| consumed),
+    }
+  }
+
+  fn flush(&mut self) -> std::io::Result<()> {
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::io::Write;
+
+  use super::{CStrWriter, Fallback};
+  use crate::strings::{StaticU16CStr, U8CStr, U8CString};
+
+  #[test]
+  fn unrepresentable_chars() {
+    let mut string = U8CString::default();
+    let mut writer = CStrWriter::new(&mut string).on_unrepresentable(Fallback::Replace('?'));
+    write!(writer, "a\u{20AC}b\0").unwrap();
+    assert_eq!(string.as_slice(), b"a\xE2\x82\xACb?");
+
+    let mut string = U8CString::default();
+    let mut writer = CStrWriter::new(&mut string).on_unrepresentable(Fallback::Skip);
+    write!(writer, "\u{e9}\0c").unwrap();
+    assert_eq!(string.as_slice(), b"\xC3\xA9c");
+
+    let mut string = U8CString::default();
+    let mut writer = CStrWriter::new(&mut string);
+    assert_eq!(writer.write(b"a\0").unwrap(), 1);
+    let err = writer.write(b"\0").unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    assert_eq!(string.as_slice(), b"a");
+  }
+
+  #[test]
+  fn invalid_utf8() {
+    let mut string = U8CString::default();
+    let mut writer = CStrWriter::new(&mut string).on_invalid_utf8(Fallback::Replace('?'));
+    writer.write_all(b"a\xFFb\xE2\x82c").unwrap();
+    assert_eq!(string.as_slice(), b"a?b?c");
+
+    let mut string = U8CString::default();
+    let mut writer = CStrWriter::new(&mut string).on_invalid_utf8(Fallback::Skip);
+    writer.write_all(b"\xC3").unwrap();
+    writer.write_all(b"\xA9\xC3").unwrap();
+    writer.write_all(b"x\xE2").unwrap();
+    writer.finish().unwrap();
+    assert_eq!(string.as_slice(), b"\xC3\xA9x");
+
+    let mut string = U8CString::default();
+    let err = CStrWriter::new(&mut string).write_all(b"a\xFF").unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+  }
+
+  #[test]
+  fn fixed_size_strings() {
+    let mut string = StaticU16CStr::<4>::zeroed();
+    let mut writer = CStrWriter::new(&mut string);
+    assert_eq!(writer.write(b"ab\xF0\x9F\x98\x80c").unwrap(), 6);
+    assert_eq!(writer.write(b"c").unwrap(), 0);
+    assert_eq!(string.as_slice(), &[b'a' as u16, b'b' as u16, 0xD83D, 0xDE00]);
+
+    let mut buf = *b"\0\0\0\0";
+    let mut cstr = unsafe { U8CStr::from_mut_slice_unchecked(&mut buf) };
+    let mut writer = CStrWriter::new(&mut cstr);
+    let err = write!(writer, "ab\u{20AC}").unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::WriteZero);
+    // Multibyte sequence that does not fit is not split
+    assert_eq!(&buf, b"ab\0\0");
+  }
+}
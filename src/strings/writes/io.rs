@@ -40,14 +40,184 @@ pub trait Write32 {
   }
 }
 
+macro_rules! read_trait {
+  ($trait:ident, $type:ty, $read:ident, $read_exact:ident) => {
+    pub trait $trait {
+      fn $read(&mut self, buf: &mut [$type]) -> std::io::Result<usize>;
+      fn $read_exact(&mut self, mut buf: &mut [$type]) -> std::io::Result<()> {
+        while !buf.is_empty() {
+          match self.$read(buf) {
+            Ok(0) => {
+              return Err(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                "failed to fill whole buffer",
+              ));
+            }
+            Ok(n) => buf = &mut buf[n..],
+            Err(ref e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+          }
+        }
+        Ok(())
+      }
+    }
+  };
+}
+
+read_trait!(Read16, u16, read16, read_exact16);
+read_trait!(Read32, u32, read32, read_exact32);
+
+macro_rules! bufread_trait {
+  ($trait:ident, $read:ident, $type:ty, $fill:ident, $consume:ident, $string:ty) => {
+    pub trait $trait: $read {
+      /// Returns the buffered contents, filling the buffer from the underlying reader if it is empty
+      /// NOTE: empty slice is returned only at the end of the stream
+      fn $fill(&mut self) -> std::io::Result<&[$type]>;
+      /// Marks `amount` units of the buffer as read
+      fn $consume(&mut self, amount: usize);
+      /// Reads units until the nul-terminator, which is consumed but not included in the string
+      /// NOTE: returns None only if the stream ends before any unit is read,
+      /// non-empty units that aren't terminated before the end of the stream are returned as Some string,
+      /// so `"a\0"` yields one string and `"a\0b"` yields two before None
+      fn read_until_nul(&mut self) -> std::io::Result<Option<$string>> {
+        let mut data = Vec::new();
+        loop {
+          let buf = match self.$fill() {
+            Ok(buf) => buf,
+            Err(ref e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+          };
+          if buf.is_empty() {
+            return Ok((!data.is_empty()).then(|| <$string>::from(data)));
+          }
+          match buf.iter().position(|unit| *unit == 0) {
+            Some(nul) => {
+              data.extend_from_slice(&buf[..nul]);
+              self.$consume(nul + 1);
+              return Ok(Some(<$string>::from(data)));
+            }
+            None => {
+              let len = buf.len();
+              data.extend_from_slice(buf);
+              self.$consume(len);
+            }
+          }
+        }
+      }
+    }
+  };
+}
+
+bufread_trait!(BufRead16, Read16, u16, fill_buf16, consume16, super::super::U16CString);
+bufread_trait!(BufRead32, Read32, u32, fill_buf32, consume32, super::super::U32CString);
+
+macro_rules! impl_reads {
+  ($read:ident, $bufread:ident, $type:ty, $cstr:ty, $read_fn:ident, $fill:ident, $consume:ident) => {
+    impl $read for &[$type] {
+      fn $read_fn(&mut self, buf: &mut [$type]) -> std::io::Result<usize> {
+        let read = core::cmp::min(buf.len(), self.len());
+        buf[..read].copy_from_slice(&self[..read]);
+        *self = &self[read..];
+        Ok(read)
+      }
+    }
+
+    impl $bufread for &[$type] {
+      fn $fill(&mut self) -> std::io::Result<&[$type]> {
+        Ok(self)
+      }
+      fn $consume(&mut self, amount: usize) {
+        *self = &self[amount..];
+      }
+    }
+
+    /// Reads the contents until nul-terminator
+    impl $read for &$cstr {
+      fn $read_fn(&mut self, buf: &mut [$type]) -> std::io::Result<usize> {
+        let data = self.as_slice_with_nul();
+        let read = core::cmp::min(buf.len(), data.len() - 1);
+        buf[..read].copy_from_slice(&data[..read]);
+        // SAFETY: the rest of the slice still ends with the nul-terminator
+        *self = unsafe { <$cstr>::from_slice_unchecked(&data[read..]) };
+        Ok(read)
+      }
+    }
+  };
+}
+
+impl_reads!(Read16, BufRead16, u16, super::super::U16CStr, read16, fill_buf16, consume16);
+impl_reads!(Read32, BufRead32, u32, super::super::U32CStr, read32, fill_buf32, consume32);
+
+// Default capacity of the buffered readers in code units
+const DEFAULT_BUF_CAPACITY: usize = 4096;
+
+macro_rules! buf_reader {
+  ($name:ident, $read:ident, $bufread:ident, $type:ty, $read_fn:ident, $fill:ident, $consume:ident) => {
+    /// Adds buffering to any reader of code units
+    #[derive(Debug)]
+    pub struct $name<R: $read> {
+      inner: R,
+      buf: Box<[$type]>,
+      pos: usize,
+      filled: usize,
+    }
+
+    impl<R: $read> $name<R> {
+      pub fn new(inner: R) -> Self {
+        Self::with_capacity(DEFAULT_BUF_CAPACITY, inner)
+      }
+      pub fn with_capacity(capacity: usize, inner: R) -> Self {
+        Self { inner, buf: vec![0; capacity].into_boxed_slice(), pos: 0, filled: 0 }
+      }
+      pub fn get_ref(&self) -> &R {
+        &self.inner
+      }
+      /// NOTE: buffered contents that were not read yet are lost
+      pub fn into_inner(self) -> R {
+        self.inner
+      }
+    }
+
+    impl<R: $read> $read for $name<R> {
+      fn $read_fn(&mut self, buf: &mut [$type]) -> std::io::Result<usize> {
+        // Large reads bypass an empty buffer
+        if self.pos == self.filled && buf.len() >= self.buf.len() {
+          return self.inner.$read_fn(buf);
+        }
+        let data = self.$fill()?;
+        let read = core::cmp::min(buf.len(), data.len());
+        buf[..read].copy_from_slice(&data[..read]);
+        self.$consume(read);
+        Ok(read)
+      }
+    }
+
+    impl<R: $read> $bufread for $name<R> {
+      fn $fill(&mut self) -> std::io::Result<&[$type]> {
+        if self.pos == self.filled {
+          self.filled = self.inner.$read_fn(&mut self.buf)?;
+          self.pos = 0;
+        }
+        Ok(&self.buf[self.pos..self.filled])
+      }
+      fn $consume(&mut self, amount: usize) {
+        self.pos = core::cmp::min(self.pos + amount, self.filled);
+      }
+    }
+  };
+}
+
+buf_reader!(BufReader16, Read16, BufRead16, u16, read16, fill_buf16, consume16);
+buf_reader!(BufReader32, Read32, BufRead32, u32, read32, fill_buf32, consume32);
+
 macro_rules! impl_for_slices {
   ($trait:ident, $type:ty, $write:ident, $flush:ident) => {
     impl $trait for &mut [$type] {
       fn $write(&mut self, buf: &[$type]) -> std::io::Result<usize> {
-        let written = std::cmp::min(buf.len(), self.len());
+        let written = core::cmp::min(buf.len(), self.len());
         self.copy_from_slice(&buf[0..written]);
         *self = unsafe {
-          std::slice::from_raw_parts_mut(self.as_mut_ptr().add(written), self.len() - written)
+          core::slice::from_raw_parts_mut(self.as_mut_ptr().add(written), self.len() - written)
         };
         // *self = &mut self[written..];
         Ok(written)
@@ -70,7 +240,7 @@ macro_rules! impl_for_vecs {
         let written = buf.len();
         let old_len = self.len();
         let written = if self.try_reserve_exact(written).is_err() {
-          std::cmp::min(self.capacity() - old_len, written)
+          core::cmp::min(self.capacity() - old_len, written)
         } else {
           written
         };
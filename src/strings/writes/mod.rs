@@ -1,3 +1,12 @@
-#[cfg(not(feature = "no_std"))]
+#[cfg(feature = "std")]
 pub mod io;
 pub mod fmt;
+#[cfg(feature = "std")]
+mod codec;
+#[cfg(feature = "std")]
+mod cstr_writer;
+
+#[cfg(feature = "std")]
+pub use codec::{ByteOrder, Utf16Decoder, Utf16Encoder, Utf16Reader, Utf32Decoder, Utf32Encoder, Utf32Reader};
+#[cfg(feature = "std")]
+pub use cstr_writer::{CStrSink, CStrWriter, Fallback};
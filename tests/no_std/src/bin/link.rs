@@ -0,0 +1,45 @@
+//! Links the checks into a bare-metal binary, so symbols missing on targets without C runtime are reported
+//! cargo build --manifest-path tests/no_std/Cargo.toml --target thumbv7em-none-eabihf --features link
+#![no_std]
+#![no_main]
+
+use core::hint::black_box;
+
+use cutils::strings::{StaticU8CStr, U8CStr};
+
+#[panic_handler]
+fn panic(_: &core::panic::PanicInfo) -> ! {
+  loop {}
+}
+
+#[cfg(feature = "alloc")]
+mod allocator {
+  use core::alloc::{GlobalAlloc, Layout};
+
+  struct NoAlloc;
+
+  unsafe impl GlobalAlloc for NoAlloc {
+    unsafe fn alloc(&self, _: Layout) -> *mut u8 {
+      core::ptr::null_mut()
+    }
+    unsafe fn dealloc(&self, _: *mut u8, _: Layout) {}
+  }
+
+  #[global_allocator]
+  static ALLOCATOR: NoAlloc = NoAlloc;
+}
+
+#[no_mangle]
+pub extern "C" fn _start() -> ! {
+  let s = StaticU8CStr::<8>::from_str_const("abc");
+  black_box(cutils_no_std_check::encode_static(black_box("abc")).ok());
+  black_box(cutils_no_std_check::inspect_u8(black_box(s.as_ref())));
+  black_box(unsafe { cutils_no_std_check::len_of(black_box(s.as_ptr())) });
+  black_box(unsafe { U8CStr::from_ptr(black_box(s.as_ptr())) }.len_usize());
+  black_box(unsafe { cutils_no_std_check::owned_len(black_box(s.as_ptr().cast_mut())) });
+  #[cfg(feature = "libc")]
+  black_box(cutils_no_std_check::copy_to_malloc(black_box(&s)));
+  #[cfg(feature = "alloc")]
+  black_box(cutils_no_std_check::decode_u16(black_box("abc")));
+  loop {}
+}
@@ -0,0 +1,49 @@
+#![no_std]
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "libc")]
+use cutils::strings::StaticU8CStr;
+use cutils::strings::{NoopDeleter, StaticU16CStr, StrError, U16CStr, U8CStr, U8OwningCStr};
+
+const CONST: StaticU16CStr<8> = StaticU16CStr::from_str_const("const");
+
+pub fn encode_static(s: &str) -> Result<usize, StrError> {
+  let encoded = StaticU16CStr::<8>::encode(s)?;
+  let borrowed: &U16CStr = encoded.as_ref();
+  Ok(borrowed.len_usize() + CONST.len_usize())
+}
+
+pub fn inspect_u8(s: &U8CStr) -> Option<&str> {
+  if *s == "-" {
+    return None;
+  }
+  s.to_str().ok()
+}
+
+#[cfg(feature = "libc")]
+pub fn copy_to_malloc(s: &StaticU8CStr<8>) -> Option<U8OwningCStr> {
+  U8OwningCStr::try_alloc_copy(s.as_ref())
+}
+
+/// # Safety
+/// `ptr` must point to a nul-terminated string
+pub unsafe fn len_of(ptr: *const u8) -> usize {
+  unsafe { U8CStr::from_ptr(ptr) }.len_usize()
+}
+
+/// # Safety
+/// `ptr` must point to a nul-terminated string
+pub unsafe fn owned_len(ptr: *mut u8) -> usize {
+  unsafe { U8OwningCStr::from_ptr_safe_deleter(ptr, NoopDeleter) }.len_usize()
+}
+
+#[cfg(feature = "alloc")]
+pub fn decode_u16(s: &str) -> alloc::string::String {
+  cutils::strings::U16CString::encode(s).unwrap().decode_lossy()
+}
+
+#[cfg(all(feature = "alloc", feature = "libc"))]
+pub fn copy_or_abort(s: &U8CStr) -> U8OwningCStr {
+  U8OwningCStr::alloc_copy(s)
+}
@@ -39,6 +39,13 @@ fn test_u16cstr_macro_unicode() {
   assert_eq!(string.as_slice(), encoded.as_slice());
 }
 #[test]
+fn test_u16cstr_macro_surrogates() {
+  let string = u16cstr!("1\u{1F600}2");
+  let encoded = widestring::u16cstr!("1\u{1F600}2");
+  assert_eq!(string.as_slice_full(), encoded.as_slice_with_nul());
+  assert_eq!(string.decode().unwrap(), "1\u{1F600}2");
+}
+#[test]
 fn test_u16cstr_macro_const_str() {
   const TEST: &str = "123";
   const STRING: &U16CStr = u16cstr!(TEST);
@@ -64,4 +71,18 @@ fn test_static_u16cstr_macro_fmt() {
   let string = static_u16cstr!("123 {} {}"; 12, 456, "abc");
   assert_eq!(string.as_slice_with_nul(), as_u16(b"123 456 abc\0"));
   assert_eq!(string.as_slice(), as_u16(b"123 456 abc"));
-}
\ No newline at end of file
+}
+#[test]
+fn test_static_u16cstr_macro_const() {
+  const STRING: StaticU16CStr<4> = static_u16cstr!(const "a\u{1F600}b"; 4);
+  assert_eq!(STRING.as_slice(), [b'a' as u16, 0xD83D, 0xDE00, b'b' as u16]);
+  let string = static_u16cstr!(const "abc"; 8);
+  assert_eq!(string.as_slice_with_nul(), as_u16(b"abc\0"));
+}
+
+#[test]
+fn test_try_static_u16cstr_macro_fmt() {
+  let string = try_static_u16cstr!("123 {}"; 7, 456).unwrap();
+  assert_eq!(string.as_slice(), as_u16(b"123 456"));
+  assert!(try_static_u16cstr!("123 {}"; 6, 456).is_err());
+}
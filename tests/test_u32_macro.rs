@@ -58,3 +58,18 @@ fn test_static_u32cstr_macro_fmt() {
   assert_eq!(string.as_slice_with_nul(), as_u32(b"123 456 abc\0"));
   assert_eq!(string.as_slice(), as_u32(b"123 456 abc"));
 }
+
+#[test]
+fn test_static_u32cstr_macro_const() {
+  const STRING: StaticU32CStr<2> = static_u32cstr!(const "a\u{1F600}"; 2);
+  assert_eq!(STRING.as_slice(), [b'a' as u32, 0x1F600]);
+  let string = static_u32cstr!(const "abc"; 8);
+  assert_eq!(string.as_slice_with_nul(), as_u32(b"abc\0"));
+}
+
+#[test]
+fn test_try_static_u32cstr_macro_fmt() {
+  let string = try_static_u32cstr!("123 {}"; 7, 456).unwrap();
+  assert_eq!(string.as_slice(), as_u32(b"123 456"));
+  assert!(try_static_u32cstr!("123 {}"; 6, 456).is_err());
+}
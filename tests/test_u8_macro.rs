@@ -48,3 +48,38 @@ fn test_static_cstr_macro_fmt() {
   assert_eq!(string.as_slice(), b"123 456 abc");
 }
 
+
+#[test]
+fn test_static_cstr_macro_const() {
+  const STRING: StaticU8CStr<3> = static_u8cstr!(const "a\u{e9}"; 3);
+  assert_eq!(STRING.as_slice(), "a\u{e9}".as_bytes());
+  let string = static_cstr!(const "abc"; 8);
+  assert_eq!(string.as_slice_with_nul(), b"abc\0");
+}
+
+#[test]
+fn test_u8_literal_paths_agree_on_utf8() {
+  let expected = "\u{e9}\u{1F600}".as_bytes();
+  const STATIC: StaticU8CStr<8> = StaticU8CStr::from_str_const("\u{e9}\u{1F600}");
+  assert_eq!(cstr!("\u{e9}\u{1F600}").as_slice(), expected);
+  assert_eq!(u8cstr!("\u{e9}\u{1F600}").as_slice(), expected);
+  assert_eq!(STATIC.as_slice(), expected);
+  assert_eq!(static_u8cstr!(const "\u{e9}\u{1F600}"; 8).as_slice(), expected);
+  assert_eq!(static_u8cstr!("\u{e9}{}"; 8, '\u{1F600}').as_slice(), expected);
+  assert_eq!(try_static_u8cstr!("{}\u{1F600}"; 8, '\u{e9}').unwrap().as_slice(), expected);
+  assert_eq!(StaticU8CStr::<8>::encode("\u{e9}\u{1F600}").unwrap().as_slice(), expected);
+}
+
+#[test]
+#[should_panic]
+fn test_static_cstr_macro_fmt_panics_on_overflow() {
+  let _ = static_u8cstr!("123 {}"; 6, 456);
+}
+
+#[test]
+fn test_try_static_cstr_macro_fmt() {
+  let string = try_static_u8cstr!("123 {}"; 7, 456).unwrap();
+  assert_eq!(string.as_slice(), b"123 456");
+  assert!(try_static_u8cstr!("123 {}"; 6, 456).is_err());
+  assert!(try_static_u8cstr!("1{}"; 6, "\0").is_err());
+}